@@ -0,0 +1,372 @@
+use crate::storage;
+use bevy::{input::InputSystem, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<InputMap>()
+            .init_resource::<Actions>()
+            .add_system_to_stage(
+                // before anything reads what the
+                // player is trying to do
+                CoreStage::PreUpdate,
+                Actions::update
+                    .system()
+                    .label(ActionSystem)
+                    .after(InputSystem),
+            );
+    }
+}
+
+// lets other input systems run after the
+// actions have been worked out
+#[derive(
+    SystemLabel, Debug, Clone, PartialEq, Eq, Hash,
+)]
+pub struct ActionSystem;
+
+// everything the player can do with a button
+// systems ask about these rather than raw keys
+// so that the keys can be changed
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Dash,
+    Pause,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    MenuConfirm,
+}
+
+impl Action {
+    // every action in the order they're
+    // shown on the controls screen
+    pub const ALL: [Action; 12] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Dash,
+        Action::Pause,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuLeft,
+        Action::MenuRight,
+        Action::MenuConfirm,
+    ];
+
+    // the name shown to the player
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Fire => "Fire",
+            Action::Dash => "Dash",
+            Action::Pause => "Pause",
+            Action::MenuUp => "Menu Up",
+            Action::MenuDown => "Menu Down",
+            Action::MenuLeft => "Menu Left",
+            Action::MenuRight => "Menu Right",
+            Action::MenuConfirm => "Menu Confirm",
+        }
+    }
+
+    // the bindings each action starts with
+    fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::*;
+        match self {
+            Action::MoveUp => vec![Key(KeyCode::W)],
+            Action::MoveDown => vec![Key(KeyCode::S)],
+            Action::MoveLeft => vec![Key(KeyCode::A)],
+            Action::MoveRight => vec![Key(KeyCode::D)],
+            Action::Fire => vec![
+                Mouse(MouseButton::Left),
+                Gamepad(GamepadButtonType::RightTrigger2),
+            ],
+            Action::Dash => vec![
+                Key(KeyCode::Space),
+                Mouse(MouseButton::Right),
+                Gamepad(GamepadButtonType::LeftTrigger2),
+            ],
+            Action::Pause => vec![
+                Key(KeyCode::Escape),
+                Key(KeyCode::P),
+                Gamepad(GamepadButtonType::Start),
+            ],
+            Action::MenuUp => vec![
+                Key(KeyCode::Up),
+                Gamepad(GamepadButtonType::DPadUp),
+            ],
+            Action::MenuDown => vec![
+                Key(KeyCode::Down),
+                Gamepad(GamepadButtonType::DPadDown),
+            ],
+            Action::MenuLeft => vec![
+                Key(KeyCode::Left),
+                Gamepad(GamepadButtonType::DPadLeft),
+            ],
+            Action::MenuRight => vec![
+                Key(KeyCode::Right),
+                Gamepad(GamepadButtonType::DPadRight),
+            ],
+            Action::MenuConfirm => vec![
+                Key(KeyCode::Return),
+                Gamepad(GamepadButtonType::South),
+            ],
+        }
+    }
+}
+
+// a single button that can trigger an action
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    // any connected gamepad's button
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    // the name shown to the player
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Mouse(button) => {
+                format!("Mouse {:?}", button)
+            }
+            Binding::Gamepad(button) => {
+                format!("Pad {:?}", button)
+            }
+        }
+    }
+
+    // gamepad bindings are kept separate from keyboard
+    // and mouse ones so rebinding one doesn't lose the other
+    fn is_gamepad(&self) -> bool {
+        matches!(self, Binding::Gamepad(_))
+    }
+
+    // the first button pressed this frame on any device
+    pub fn just_pressed(
+        keys: &Input<KeyCode>,
+        mouse_buttons: &Input<MouseButton>,
+        gamepad_buttons: &Input<GamepadButton>,
+    ) -> Option<Binding> {
+        keys.get_just_pressed()
+            .next()
+            .map(|key| Binding::Key(*key))
+            .or_else(|| {
+                mouse_buttons
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| Binding::Mouse(*button))
+            })
+            .or_else(|| {
+                gamepad_buttons
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| {
+                        Binding::Gamepad(button.1)
+                    })
+            })
+    }
+}
+
+// which buttons trigger which actions
+// saved so that changes are kept between sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| {
+                    (*action, action.default_bindings())
+                })
+                .collect(),
+        }
+    }
+}
+
+impl FromWorld for InputMap {
+    // loading the saved bindings, or the defaults
+    // if there aren't any or they can't be read
+    fn from_world(_: &mut World) -> Self {
+        let mut map = storage::read(InputMap::KEY)
+            .and_then(|text| {
+                ron::from_str::<InputMap>(&text).ok()
+            })
+            .unwrap_or_default();
+        // actions added since the bindings were
+        // saved get their default bindings
+        for action in Action::ALL.iter() {
+            map.bindings.entry(*action).or_insert_with(
+                || action.default_bindings(),
+            );
+        }
+        map
+    }
+}
+
+impl InputMap {
+    // the name the bindings are stored under
+    const KEY: &'static str = "bindings";
+
+    // all the buttons bound to an action
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map(|bindings| bindings.as_slice())
+            .unwrap_or(&[])
+    }
+
+    // binds an action to a new button replacing
+    // whatever was bound on the same kind of device
+    pub fn bind(
+        &mut self,
+        action: Action,
+        binding: Binding,
+    ) {
+        let bindings =
+            self.bindings.entry(action).or_default();
+        bindings.retain(|other| {
+            other.is_gamepad() != binding.is_gamepad()
+        });
+        bindings.push(binding);
+    }
+
+    // writes the bindings to storage
+    pub fn save(&self) {
+        let result = ron::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                storage::write(Self::KEY, &text)
+            });
+        if let Err(e) = result {
+            error!("Couldn't save the bindings: {}", e);
+        }
+    }
+}
+
+// which actions are being done this frame
+#[derive(Debug, Default)]
+pub struct Actions {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    just_released: HashSet<Action>,
+}
+
+impl Actions {
+    // whether any of the action's buttons are held
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    // whether one of the action's buttons
+    // was pressed this frame
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    // whether the action stopped being held this frame
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+
+    // stops an action counting as just pressed for
+    // the rest of the frame so it isn't handled twice
+    pub fn consume(&mut self, action: Action) {
+        self.just_pressed.remove(&action);
+    }
+
+    // works out every action from the buttons
+    // that are bound to it
+    fn update(
+        mut actions: ResMut<Actions>,
+        map: Res<InputMap>,
+        keys: Res<Input<KeyCode>>,
+        mouse_buttons: Res<Input<MouseButton>>,
+        gamepad_buttons: Res<Input<GamepadButton>>,
+    ) {
+        // checks a binding with the given method for
+        // keys and mice and the given iterator for
+        // gamepads (as any gamepad will do)
+        macro_rules! check {
+            ($binding:expr, $method:ident, $iter:ident) => {
+                match $binding {
+                    Binding::Key(key) => keys.$method(*key),
+                    Binding::Mouse(button) => {
+                        mouse_buttons.$method(*button)
+                    }
+                    Binding::Gamepad(button) => {
+                        gamepad_buttons
+                            .$iter()
+                            .any(|pad| pad.1 == *button)
+                    }
+                }
+            };
+        }
+        let actions = &mut *actions;
+        actions.pressed.clear();
+        actions.just_pressed.clear();
+        actions.just_released.clear();
+        for action in Action::ALL.iter() {
+            let bindings = map.bindings(*action);
+            if bindings
+                .iter()
+                .any(|b| check!(b, pressed, get_pressed))
+            {
+                actions.pressed.insert(*action);
+            }
+            if bindings.iter().any(|b| {
+                check!(b, just_pressed, get_just_pressed)
+            }) {
+                actions.just_pressed.insert(*action);
+            }
+            if !actions.pressed.contains(action)
+                && bindings.iter().any(|b| {
+                    check!(
+                        b,
+                        just_released,
+                        get_just_released
+                    )
+                })
+            {
+                actions.just_released.insert(*action);
+            }
+        }
+    }
+}
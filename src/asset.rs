@@ -1,4 +1,4 @@
-use crate::state;
+use crate::{item, settings, state};
 use bevy::prelude::*;
 
 // putting file pathes in one centralised
@@ -25,10 +25,16 @@ impl Plugin for AssetPlugin {
                 .with_system(
                     Materials::check_loaded.system(),
                 ),
-            )
-            .insert_resource(ClearColor(
-                Color::hex("14080E").unwrap(),
-            ));
+            );
+        // matching the background to the palette
+        let palette = app
+            .world_mut()
+            .get_resource::<settings::Settings>()
+            .map(|settings| settings.palette)
+            .unwrap_or_default();
+        app.insert_resource(Materials::clear_color(
+            palette,
+        ));
     }
 }
 
@@ -38,68 +44,151 @@ impl Plugin for AssetPlugin {
 pub struct Materials {
     pub player_body: Handle<ColorMaterial>,
     pub button_normal: Handle<ColorMaterial>,
+    pub button_hovered: Handle<ColorMaterial>,
+    pub button_pressed: Handle<ColorMaterial>,
+    pub menu_overlay: Handle<ColorMaterial>,
     pub tile_empty: Handle<ColorMaterial>,
     pub tile_wall: Handle<ColorMaterial>,
     pub player_gun: Handle<ColorMaterial>,
     pub enemy: Handle<ColorMaterial>,
+    pub enemy_shooter: Handle<ColorMaterial>,
+    pub enemy_tank: Handle<ColorMaterial>,
+    pub enemy_swarmer: Handle<ColorMaterial>,
+    pub enemy_turret: Handle<ColorMaterial>,
+    pub enemy_bullet: Handle<ColorMaterial>,
     pub main_font: Handle<Font>,
 }
 
 impl FromWorld for Materials {
     // called when intialising the game
     fn from_world(world: &mut World) -> Self {
+        // the colors depend on the settings which are
+        // loaded before this (or the default ones if
+        // there aren't any settings like in the simulation)
+        let palette = world
+            .get_resource::<settings::Settings>()
+            .map(|settings| settings.palette)
+            .unwrap_or_default();
+        // retriving the asset server to allow loading
+        // of more complex assets (from the filesystem)
+        let asset_server =
+            world.get_resource::<AssetServer>().unwrap();
+        // loading the font from it's path
+        let main_font = asset_server.load(file_path::FONT);
         // creates and manages handles to colors for the shader
         let mut colors = world
             .get_resource_mut::<Assets<ColorMaterial>>()
             .unwrap();
         // macro to reduce boilerplate
-        macro_rules! hex {
-            ($hex:literal) => {
-                colors.add(Color::hex($hex).unwrap().into())
+        // (the actual colors are filled in by recolor)
+        macro_rules! color {
+            () => {
+                colors.add(ColorMaterial::default())
             };
         }
 
-        // creating handle to the color of the player
-        let player_body = hex!("0038A8");
-        // creating handle to the color of the main menu button
-        let button_normal = hex!("14080e");
-        // adding new tile color materials
-        let tile_empty = hex!("14080E");
-        let tile_wall = hex!("271c47");
-        let player_gun = hex!("D70270");
-        let enemy = hex!("734F96");
-        // retriving the asset server to allow loading
-        // of more complex assets (from the filesystem)
-        let asset_server =
-            world.get_resource::<AssetServer>().unwrap();
-        // loading the font from it's path
-        let main_font = asset_server.load(file_path::FONT);
-
-        Self {
-            player_body,
-            button_normal,
-            tile_empty,
-            tile_wall,
-            player_gun,
+        let materials = Self {
+            player_body: color!(),
+            button_normal: color!(),
+            button_hovered: color!(),
+            button_pressed: color!(),
+            menu_overlay: color!(),
+            tile_empty: color!(),
+            tile_wall: color!(),
+            player_gun: color!(),
             main_font,
-            enemy,
-        }
+            enemy: color!(),
+            enemy_shooter: color!(),
+            enemy_tank: color!(),
+            enemy_swarmer: color!(),
+            enemy_turret: color!(),
+            enemy_bullet: color!(),
+        };
+        materials.recolor(palette, &mut colors);
+        materials
     }
 }
 
+// every color in the game for each palette
+// written as field => neon, high contrast
+macro_rules! palette {
+    ($($field:ident => $neon:literal, $contrast:literal;)*) => {
+        impl Materials {
+            // sets every color to the one it
+            // has in the given palette
+            pub fn recolor(
+                &self,
+                palette: settings::Palette,
+                colors: &mut Assets<ColorMaterial>,
+            ) {
+                $(
+                    let hex = match palette {
+                        settings::Palette::Neon => $neon,
+                        settings::Palette::HighContrast => {
+                            $contrast
+                        }
+                    };
+                    if let Some(material) =
+                        colors.get_mut(&self.$field)
+                    {
+                        material.color = Color::hex(hex).unwrap();
+                    }
+                )*
+            }
+        }
+    };
+}
+
+palette! {
+    // the color of the player
+    player_body => "0038A8", "00A2FF";
+    // the colors of a menu button when it's left alone,
+    // hovered over (or focused) and being pressed
+    button_normal => "271c47", "404040";
+    button_hovered => "3d2c6e", "808080";
+    button_pressed => "D70270", "FFFF00";
+    // see through so the level still shows
+    // behind the pause menu
+    menu_overlay => "14080EC0", "000000C0";
+    tile_empty => "14080E", "000000";
+    tile_wall => "271c47", "808080";
+    player_gun => "D70270", "FFFF00";
+    enemy => "734F96", "FF0000";
+    // a different color for each kind of enemy
+    enemy_shooter => "E8A33D", "FF8000";
+    enemy_tank => "4B2E83", "C00000";
+    enemy_swarmer => "B388EB", "FF60FF";
+    enemy_turret => "5C5470", "00FF00";
+    enemy_bullet => "FF9E40", "FFFFFF";
+}
+
 impl Materials {
+    // the background color in each palette
+    pub fn clear_color(
+        palette: settings::Palette,
+    ) -> ClearColor {
+        ClearColor(
+            Color::hex(match palette {
+                settings::Palette::Neon => "14080E",
+                settings::Palette::HighContrast => "000000",
+            })
+            .unwrap(),
+        )
+    }
+
     // checks to see if assets are loaded in the loading screen
     fn check_loaded(
         asset_server: Res<AssetServer>,
         materials: Res<Materials>,
+        items: Res<item::ItemCatalogue>,
         mut game_state: ResMut<State<state::GameState>>,
     ) {
         // list of all the assets that should be waited
         // on to load, so every field of materials
         // besides colors as they load instantaneously
-        // right now we only have the font but later
-        // i'm going to need to add more
-        let assets = [materials.main_font.id];
+        // (and the items file)
+        let assets =
+            [materials.main_font.id, items.handle().id];
         // checks to see if all the assets are loaded
         match asset_server.get_group_load_state(assets) {
             // if all of them are loaded then enter the main menu
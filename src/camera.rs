@@ -0,0 +1,81 @@
+use crate::{grid, player};
+use bevy::prelude::*;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // always running so the camera still settles
+        // on the player during the countdown
+        app.add_system(CameraFollow::follow.system());
+    }
+}
+
+// attach to a camera to make it follow
+// the player around the level
+pub struct CameraFollow {
+    // roughly how many seconds the camera takes
+    // to catch up with the player
+    // (zero snaps straight to them)
+    pub smoothing: f32,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self { smoothing: 0.15 }
+    }
+}
+
+impl CameraFollow {
+    // moves the camera towards the player whilst
+    // keeping the view inside the level's bounds
+    fn follow(
+        time: Res<Time>,
+        windows: Res<Windows>,
+        grid: Res<grid::Grid>,
+        player: Query<
+            (&Transform, ChangeTrackers<player::Player>),
+            Without<CameraFollow>,
+        >,
+        mut cameras: Query<(&mut Transform, &CameraFollow)>,
+    ) {
+        // no player means there's nothing to follow
+        let (player, tracker) = match player.single() {
+            Ok(player) => player,
+            Err(_) => return,
+        };
+        let window = windows.get_primary().unwrap();
+        // the furthest the camera can be from the center
+        // of the level without showing past its edges
+        // (if the level is smaller than the window
+        // then it just stays centered)
+        let half_view =
+            Vec2::new(window.width(), window.height())
+                / 2.0;
+        let max = (grid.size().to_world() / 2.0
+            - half_view)
+            .max(Vec2::ZERO);
+        let target =
+            player.translation.truncate().clamp(-max, max);
+        for (mut transform, follow) in cameras.iter_mut() {
+            let pos = transform.translation.truncate();
+            // snapping to the player when they've just spawned
+            // so the camera doesn't glide across the new level
+            let t = if tracker.is_added()
+                || follow.smoothing <= 0.0
+            {
+                1.0
+            } else {
+                // framerate independent smoothing
+                1.0 - (-time.delta_seconds()
+                    / follow.smoothing)
+                    .exp()
+            };
+            let pos = pos.lerp(target, t);
+            // keeping the camera's z as that's what
+            // makes the sprites visible
+            transform.translation.x = pos.x;
+            transform.translation.y = pos.y;
+        }
+    }
+}
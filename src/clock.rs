@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+pub struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // (the simulation swaps in a fixed clock)
+        app.init_resource::<Clock>().add_system_to_stage(
+            CoreStage::First,
+            // the engine measures the frame at the very
+            // start of this stage so it's already known
+            Clock::tick.system(),
+        );
+    }
+}
+
+// the time the game's logic runs on
+// normally this just follows the engine's time but
+// it can step by the same amount every frame instead
+// so that a run plays out exactly the same no matter
+// how fast the computer is (bevy's own Time can't
+// be set by hand)
+#[derive(Debug, Default)]
+pub struct Clock {
+    step: Option<Duration>,
+    delta: Duration,
+    elapsed: Duration,
+}
+
+impl Clock {
+    // creates a clock that moves on by step every
+    // frame regardless of how long the frame took
+    pub fn fixed(step: Duration) -> Self {
+        Self {
+            step: Some(step),
+            ..Default::default()
+        }
+    }
+
+    // the time between this frame and the last
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    // the time between this frame and the last
+    // in seconds
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    // how much time has gone by since the game
+    // started in seconds
+    pub fn seconds_since_startup(&self) -> f64 {
+        self.elapsed.as_secs_f64()
+    }
+
+    // moves the clock on by either the fixed step
+    // or however long the last frame took
+    fn tick(time: Res<Time>, mut clock: ResMut<Clock>) {
+        clock.delta =
+            clock.step.unwrap_or_else(|| time.delta());
+        clock.elapsed += clock.delta;
+    }
+}
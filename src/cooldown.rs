@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::state;
+use crate::{clock, state};
 use bevy::prelude::*;
 
 pub struct CooldownPlugin;
@@ -64,6 +64,23 @@ impl Cooldown {
         Self { timer }
     }
 
+    // how far through the cooldown is from zero
+    // to one (a null cooldown never gets anywhere)
+    pub fn progress(&self) -> f32 {
+        match self.timer {
+            Some(ref timer) => timer.percent(),
+            None => 0.0,
+        }
+    }
+
+    // ends the cooldown as of the next tick
+    pub fn finish(&mut self) {
+        if let Some(ref mut timer) = self.timer {
+            let duration = timer.duration();
+            timer.set_elapsed(duration)
+        };
+    }
+
     // manually sets the time elapsed on the timer
     pub fn set_elapsed(&mut self, secs: f32) {
         if let Some(ref mut timer) = self.timer {
@@ -72,14 +89,14 @@ impl Cooldown {
     }
 
     // updates each cooldown's internal
-    // timer with the engine's meausured time
+    // timer with the game clock's time
     // delta
     pub fn tick(
-        time: Res<Time>,
+        clock: Res<clock::Clock>,
         mut query: Query<&mut Cooldown>,
     ) {
         // calculating the time elapsed since the last frame
-        let delta = time.delta();
+        let delta = clock.delta();
         // advancing all the cooldown timers by that amount
         for mut cooldown in query.iter_mut() {
             if let Some(ref mut timer) = cooldown.timer {
@@ -1,6 +1,14 @@
-use crate::{asset, grid, phys, player, state};
+use crate::{
+    asset, cooldown, grid, health, phys, player, seed,
+    state,
+};
 use bevy::{prelude::DespawnRecursiveExt, prelude::*};
 use bevy_rapier2d::prelude::*;
+use rand::{seq::IteratorRandom, SeedableRng};
+
+mod archetype;
+pub use archetype::{Archetype, Behaviour, EnemyGun};
+pub mod bullet;
 
 pub struct EnemyPlugin;
 
@@ -14,37 +22,111 @@ impl Plugin for EnemyPlugin {
         .add_system_set(
             SystemSet::on_update(state::GameState::InLevel)
                 .with_system(Enemy::path.system())
-                .with_system(Enemy::collide.system())
-                .with_system(Enemy::check_cleared.system()),
+                .with_system(Enemy::die.system())
+                .with_system(Enemy::check_cleared.system())
+                .with_system(
+                    bullet::EnemyBullet::fire.system(),
+                )
+                .with_system(
+                    bullet::EnemyBullet::lifetime.system(),
+                )
+                .with_system(
+                    bullet::EnemyBullet::collide.system(),
+                ),
         )
+        .add_system(state::GameState::despawn::<Enemy>(
+            state::GameState::InLevel,
+        ))
+        // cleaning up any enemy bullets still flying
+        // at the end of the level
         .add_system(state::GameState::despawn::<
-            Enemy,
-        >(
-            state::GameState::InLevel
-        ));
+            bullet::EnemyBullet,
+        >(state::GameState::InLevel))
+        .init_resource::<PatrolRng>();
+    }
+}
+
+// where the enemies get the random numbers for
+// wandering about from, taken from the run's seed
+// so a level always plays out the same way
+pub struct PatrolRng(seed::GameRng);
+
+impl Default for PatrolRng {
+    // replaced as soon as a level starts
+    fn default() -> Self {
+        Self(seed::GameRng::seed_from_u64(0))
     }
 }
 
 pub struct Enemy {
+    // what kind of enemy this is
+    archetype: Archetype,
+    // where the enemy last saw the player
     target: Option<Vec2>,
+    // whether the player was in view last time
+    // the enemy looked
+    sees_player: bool,
+    // where the enemy is wandering to when
+    // it doesn't know where the player is
+    patrol: Option<grid::GridPos>,
+    // the tiles left to walk through to get to
+    // route_goal (stored backwards so the next
+    // one can be popped off the end)
+    route: Vec<grid::GridPos>,
+    route_goal: Option<grid::GridPos>,
 }
 
 impl Enemy {
+    // how much slower than its top speed
+    // an enemy wanders when patrolling
+    const PATROL_FACTOR: f32 = 0.4;
+    // how many tiles away a patrol point can be
+    const PATROL_RANGE: usize = 4;
+    // how much running into the player hurts them
+    const CONTACT_DAMAGE: f32 = 1.0;
+    // enemies can always be hurt as the gun makes sure
+    // each bullet only hurts an enemy once, so shots
+    // landing together all count
+    const IFRAMES: f32 = 0.0;
+
+    // creates an enemy of the given kind that
+    // doesn't know where anything is yet
+    fn new(archetype: Archetype) -> Self {
+        Self {
+            archetype,
+            target: None,
+            sees_player: false,
+            patrol: None,
+            route: Vec::new(),
+            route_goal: None,
+        }
+    }
+
     // spawns in every enemy according to where the
     // grid says they should be
     pub fn spawn(
         mut commands: Commands,
         grid: Res<grid::Grid>,
         materials: Res<asset::Materials>,
+        run_seed: Res<seed::RunSeed>,
+        difficulty: Res<grid::Difficulty>,
+        mut patrol_rng: ResMut<PatrolRng>,
     ) {
+        // restarting a level starts the wandering
+        // over from the beginning too
+        *patrol_rng = PatrolRng(run_seed.rng(
+            difficulty.level(),
+            seed::RngStream::Patrol,
+        ));
         // using batched spawning to speed up
         // the level load
         commands.spawn_batch(
             grid.enemies
                 .iter()
-                .map(|pos| {
+                .map(|(pos, archetype)| {
                     EnemyBundle::new(
-                        pos.to_world(),
+                        pos.to_world(grid.size()),
+                        *archetype,
                         &materials,
                     )
                 })
@@ -65,6 +147,8 @@ impl Enemy {
             (Entity, &Transform),
             With<player::Player>,
         >,
+        grid: Res<grid::Grid>,
+        mut patrol_rng: ResMut<PatrolRng>,
         phys: Res<QueryPipeline>,
         collider_query: QueryPipelineColliderComponentsQuery,
     ) {
@@ -76,8 +160,11 @@ impl Enemy {
             QueryPipelineColliderComponentsSet(
                 &collider_query,
             );
+        let size = grid.size();
         enemies.for_each_mut(|(mut vel, pos, mut enemy)| {
             let pos: Vec2 = pos.position.translation.into();
+            let stats = enemy.archetype.stats();
+            enemy.sees_player = false;
             // if the enemy has reached their target
             // then stop trying to path to it
             if let Some(target) = enemy.target {
@@ -111,6 +198,8 @@ impl Enemy {
                 // target to the player's position
                 if handle.entity() == player_id {
                     enemy.target = Some(player);
+                    enemy.patrol = None;
+                    enemy.sees_player = true;
                 }
                 // no else clause as otherwise
                 // line of sight to the player is blocked
@@ -119,60 +208,173 @@ impl Enemy {
                 // where it last saw the player or
                 // if it hasn't seen the player or
                 // has reached where it last saw the
-                // player stand still
-
-                // calculating the direction that the
-                // enemy should move in
-                vel.linvel =
-                    // if the enemy has a target then move 
-                    // in the direction that target is  
-                    // relativeto itself at a 
-                    // speed of 250px per second
-                    if let Some(target) = enemy.target {
-                        ((target - pos).normalize() * 250.0)
-                            .into()
-                    } else {
-                        // if the enemy has no target
-                        // then just don't move
-                        Vec2::ZERO.into()
-                    }
+                // player go back to patrolling
             }
+
+            // calculating the direction that the
+            // enemy should move in
+            vel.linvel =
+                match (stats.behaviour, enemy.target) {
+                    // some enemies never move
+                    (Behaviour::Stationary, _) => {
+                        Vec2::ZERO
+                    }
+                    // close enough to the player so hold
+                    // position rather than getting closer
+                    (
+                        Behaviour::KeepDistance(range),
+                        Some(target),
+                    ) if enemy.sees_player
+                        && pos.distance(target)
+                            <= range =>
+                    {
+                        Vec2::ZERO
+                    }
+                    // if the enemy has a target then follow
+                    // the grid towards it, or if there's no
+                    // route go straight at it
+                    (_, Some(target)) => {
+                        enemy
+                            .steer(&grid, pos, target)
+                            .unwrap_or_else(|| {
+                                (target - pos)
+                                    .normalize_or_zero()
+                            })
+                            * stats.speed
+                    }
+                    // if the enemy has no target then
+                    // wander around where it is
+                    (_, None) => {
+                        let tile =
+                            grid::GridPos::from_world(
+                                pos, size,
+                            );
+                        // picking somewhere new to go if there's
+                        // nowhere to go or it's already there
+                        let patrol = match enemy.patrol {
+                            Some(patrol)
+                                if patrol != tile =>
+                            {
+                                patrol
+                            }
+                            _ => Enemy::patrol_point(
+                                &grid,
+                                tile,
+                                &mut patrol_rng.0,
+                            ),
+                        };
+                        enemy.patrol = Some(patrol);
+                        match enemy.steer(
+                            &grid,
+                            pos,
+                            patrol.to_world(size),
+                        ) {
+                            Some(dir) => {
+                                dir * stats.speed
+                                    * Enemy::PATROL_FACTOR
+                            }
+                            // it can't get there so pick
+                            // somewhere else next frame
+                            None => {
+                                enemy.patrol = None;
+                                Vec2::ZERO
+                            }
+                        }
+                    }
+                }
+                .into();
         })
     }
 
-    pub fn collide(
+    // works out which direction to move in to follow
+    // the grid to goal, replanning the route if the goal
+    // has moved to a different tile
+    // returns None if there's no route there
+    fn steer(
+        &mut self,
+        grid: &grid::Grid,
+        pos: Vec2,
+        goal: Vec2,
+    ) -> Option<Vec2> {
+        let size = grid.size();
+        let goal_tile =
+            grid::GridPos::from_world(goal, size);
+        if self.route_goal != Some(goal_tile) {
+            self.route_goal = Some(goal_tile);
+            self.route = grid
+                .path_between(
+                    grid::GridPos::from_world(pos, size),
+                    goal_tile,
+                    true,
+                    grid::Tile::walk_cost,
+                )
+                .map(|path| path.positions)
+                .unwrap_or_default();
+            // flipping it so the next tile is at the end
+            // and removing the tile the enemy is already on
+            self.route.reverse();
+            self.route.pop();
+        }
+        // dropping any tiles that have been reached
+        while let Some(next) = self.route.last() {
+            if pos.abs_diff_eq(next.to_world(size), 4.0) {
+                self.route.pop();
+            } else {
+                break;
+            }
+        }
+        match self.route.last() {
+            Some(next) => Some(
+                (next.to_world(size) - pos)
+                    .normalize_or_zero(),
+            ),
+            // on the goal's tile so head straight for it
+            None if grid::GridPos::from_world(
+                pos, size,
+            ) == goal_tile =>
+            {
+                Some((goal - pos).normalize_or_zero())
+            }
+            None => None,
+        }
+    }
+
+    // picks a random open tile near the enemy
+    // for it to wander to
+    fn patrol_point(
+        grid: &grid::Grid,
+        tile: grid::GridPos,
+        rng: &mut seed::GameRng,
+    ) -> grid::GridPos {
+        let range = Enemy::PATROL_RANGE as isize;
+        grid::GridPos::iter_all(grid.size())
+            .filter(|pos| {
+                (pos.x as isize - tile.x as isize).abs()
+                    <= range
+                    && (pos.y as isize - tile.y as isize)
+                        .abs()
+                        <= range
+                    && grid[*pos].walk_cost().is_some()
+            })
+            .choose(rng)
+            // nowhere to go so stay put
+            .unwrap_or(tile)
+    }
+
+    // despawns any enemies that have run out of health
+    pub fn die(
         mut commands: Commands,
-        mut contact_events: EventReader<ContactEvent>,
-        enemies: Query<(), With<Enemy>>,
-        bullets: Query<(), With<player::bullet::Bullet>>,
+        enemies: Query<
+            (Entity, &health::Health),
+            With<Enemy>,
+        >,
+        mut game_events: EventWriter<state::GameEvent>,
     ) {
-        for contact in contact_events.iter() {
-            // only dealing with initial collisions
-            if let ContactEvent::Started(h1, h2) = contact {
-                // getting the entity handles
-                // from the physics handles
-                let (e1, e2) = (h1.entity(), h2.entity());
-                // checking both
-                // that e1 is an enemy or bullet
-                // and that e2 is an enemy or bullet
-                for (bullet, enemy) in [(e1, e2), (e2, e1)]
-                {
-                    // if the bullet is a bullet and the
-                    // enemy is an enemy
-                    if enemies.get(enemy).is_ok()
-                        && bullets.get(bullet).is_ok()
-                    {
-                        // despawn the bullet
-                        // and the enemy
-                        commands
-                            .entity(bullet)
-                            .despawn_recursive();
-                        commands
-                            .entity(enemy)
-                            .despawn_recursive();
-                        break;
-                    }
-                }
+        for (enemy, health) in enemies.iter() {
+            if health.is_dead() {
+                commands.entity(enemy).despawn_recursive();
+                game_events
+                    .send(state::GameEvent::EnemyKilled);
             }
         }
     }
@@ -192,6 +394,12 @@ impl Enemy {
 #[derive(Bundle)]
 pub struct EnemyBundle {
     enemy: Enemy,
+    // time until the enemy can shoot again
+    // (null for enemies that can't shoot)
+    reload: cooldown::Cooldown,
+    health: health::Health,
+    // enemies hurt the player by running into them
+    damage: health::Damage,
     sync: ColliderPositionSync,
     #[bundle]
     collider: ColliderBundle,
@@ -206,13 +414,30 @@ impl EnemyBundle {
     // all the required components
     pub fn new(
         pos: Vec2,
+        archetype: Archetype,
         materials: &asset::Materials,
     ) -> Self {
+        let stats = archetype.stats();
         Self {
-            enemy: Enemy { target: None },
+            enemy: Enemy::new(archetype),
+            health: health::Health::new(
+                health::Team::Enemy,
+                stats.health,
+                Enemy::IFRAMES,
+            ),
+            damage: health::Damage {
+                team: health::Team::Enemy,
+                amount: Enemy::CONTACT_DAMAGE,
+            },
+            reload: cooldown::Cooldown::new(
+                stats.gun.as_ref().map(|gun| gun.cooldown),
+            ),
             sync: ColliderPositionSync::Discrete,
             collider: ColliderBundle {
-                shape: ColliderShape::cuboid(10.0, 10.0),
+                shape: ColliderShape::cuboid(
+                    stats.size.x / 2.0,
+                    stats.size.y / 2.0,
+                ),
                 material: ColliderMaterial {
                     restitution: 0.0,
                     friction: 0.0,
@@ -228,6 +453,13 @@ impl EnemyBundle {
                 ..Default::default()
             },
             rigid_body: RigidBodyBundle {
+                // enemies that never move can't be pushed
+                body_type: match stats.behaviour {
+                    Behaviour::Stationary => {
+                        RigidBodyType::Static
+                    }
+                    _ => RigidBodyType::Dynamic,
+                },
                 position: pos.into(),
                 mass_properties:
                     RigidBodyMassPropsFlags::ROTATION_LOCKED
@@ -238,8 +470,8 @@ impl EnemyBundle {
                 transform: Transform::from_translation(
                     pos.extend(4.0),
                 ),
-                material: materials.enemy.clone(),
-                sprite: Sprite::new(Vec2::new(20.0, 20.0)),
+                material: archetype.material(materials),
+                sprite: Sprite::new(stats.size),
                 ..Default::default()
             },
         }
@@ -0,0 +1,153 @@
+use crate::asset;
+use bevy::prelude::*;
+
+// the different kinds of enemy that can be
+// placed in a level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Archetype {
+    Chaser,
+    Shooter,
+    Tank,
+    Swarmer,
+    Turret,
+}
+
+// how an enemy moves once it knows where the player is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Behaviour {
+    // runs straight at the player
+    Chase,
+    // closes in until it can see the player
+    // from this far away and then holds
+    KeepDistance(f32),
+    // never moves at all
+    Stationary,
+}
+
+// all the numbers that make an archetype
+// behave the way it does
+#[derive(Debug, Clone)]
+pub struct ArchetypeStats {
+    pub size: Vec2,
+    pub speed: f32,
+    // how much damage it takes to kill
+    pub health: f32,
+    pub behaviour: Behaviour,
+    // how many difficulty points it costs to place
+    pub cost: f32,
+    // the first level it can appear on
+    pub min_level: u32,
+    // how it shoots at the player if it can
+    pub gun: Option<EnemyGun>,
+}
+
+// how an enemy that can shoot fires its bullets
+#[derive(Debug, Clone)]
+pub struct EnemyGun {
+    // seconds between each shot
+    pub cooldown: f32,
+    pub speed: f32,
+    // seconds before a bullet disappears
+    pub lifetime: f32,
+    // how much each bullet hurts the player
+    pub damage: f32,
+    // how much of the player's movement to aim ahead
+    // for, zero aims where they are and one aims
+    // exactly where they'll be if they keep going
+    pub lead: f32,
+}
+
+impl Archetype {
+    // every archetype so that generation
+    // can pick between them
+    pub const ALL: [Archetype; 5] = [
+        Archetype::Chaser,
+        Archetype::Shooter,
+        Archetype::Tank,
+        Archetype::Swarmer,
+        Archetype::Turret,
+    ];
+
+    // the stats for each archetype
+    pub fn stats(&self) -> ArchetypeStats {
+        match self {
+            Archetype::Chaser => ArchetypeStats {
+                size: Vec2::new(20.0, 20.0),
+                speed: 250.0,
+                health: 1.0,
+                behaviour: Behaviour::Chase,
+                cost: 300.0,
+                min_level: 1,
+                gun: None,
+            },
+            Archetype::Shooter => ArchetypeStats {
+                size: Vec2::new(20.0, 20.0),
+                speed: 180.0,
+                health: 1.0,
+                behaviour: Behaviour::KeepDistance(250.0),
+                cost: 450.0,
+                min_level: 3,
+                gun: Some(EnemyGun {
+                    cooldown: 1.2,
+                    speed: 300.0,
+                    lifetime: 2.0,
+                    damage: 1.0,
+                    lead: 1.0,
+                }),
+            },
+            Archetype::Tank => ArchetypeStats {
+                size: Vec2::new(30.0, 30.0),
+                speed: 150.0,
+                health: 3.0,
+                behaviour: Behaviour::Chase,
+                cost: 550.0,
+                min_level: 2,
+                gun: None,
+            },
+            Archetype::Swarmer => ArchetypeStats {
+                size: Vec2::new(12.0, 12.0),
+                speed: 320.0,
+                health: 1.0,
+                behaviour: Behaviour::Chase,
+                cost: 150.0,
+                min_level: 1,
+                gun: None,
+            },
+            Archetype::Turret => ArchetypeStats {
+                size: Vec2::new(24.0, 24.0),
+                speed: 0.0,
+                health: 2.0,
+                behaviour: Behaviour::Stationary,
+                cost: 350.0,
+                min_level: 2,
+                gun: Some(EnemyGun {
+                    cooldown: 0.8,
+                    speed: 250.0,
+                    lifetime: 3.0,
+                    damage: 1.0,
+                    lead: 0.5,
+                }),
+            },
+        }
+    }
+
+    // the color each archetype is drawn in
+    pub fn material(
+        &self,
+        materials: &asset::Materials,
+    ) -> Handle<ColorMaterial> {
+        match self {
+            Archetype::Chaser => materials.enemy.clone(),
+            Archetype::Shooter => {
+                materials.enemy_shooter.clone()
+            }
+            Archetype::Tank => materials.enemy_tank.clone(),
+            Archetype::Swarmer => {
+                materials.enemy_swarmer.clone()
+            }
+            Archetype::Turret => {
+                materials.enemy_turret.clone()
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use super::Enemy;
+use crate::{
+    asset, cooldown, health, just_spawned, phys, player,
+};
+use bevy::prelude::{DespawnRecursiveExt, *};
+use bevy_rapier2d::prelude::*;
+
+// marks a bullet fired by an enemy so it can be
+// told apart from the player's bullets
+pub struct EnemyBullet;
+
+impl EnemyBullet {
+    // how big every enemy bullet is
+    const SIZE: f32 = 8.0;
+
+    // makes every enemy with a gun that can see the player
+    // shoot at them once its reload is over
+    pub fn fire(
+        mut commands: Commands,
+        mut enemies: Query<(
+            &Enemy,
+            &RigidBodyPosition,
+            &mut cooldown::Cooldown,
+        )>,
+        player: Query<
+            (&Transform, &RigidBodyVelocity),
+            With<player::Player>,
+        >,
+        materials: Res<asset::Materials>,
+    ) {
+        let (player, player_vel) = match player.single() {
+            Ok(player) => player,
+            Err(_) => return,
+        };
+        let player = player.translation.truncate();
+        let player_vel: Vec2 = player_vel.linvel.into();
+        for (enemy, pos, mut reload) in enemies.iter_mut() {
+            let gun = match enemy.archetype.stats().gun {
+                Some(gun) => gun,
+                None => continue,
+            };
+            // no point shooting at something it can't see
+            if !enemy.sees_player || !reload.is_over() {
+                continue;
+            }
+            let pos: Vec2 = pos.position.translation.into();
+            // roughly how long the bullet will take to
+            // get to the player so it can aim for where
+            // they're going to be instead of where they are
+            let travel = pos.distance(player) / gun.speed;
+            let target =
+                player + player_vel * travel * gun.lead;
+            let dir = (target - pos).normalize_or_zero();
+            commands.spawn_bundle(EnemyBulletBundle::new(
+                &gun, dir, pos, &materials,
+            ));
+            reload.reset();
+        }
+    }
+
+    // despawns enemy bullets once they've
+    // been flying too long
+    pub fn lifetime(
+        mut commands: Commands,
+        bullets: Query<
+            (Entity, &cooldown::Cooldown),
+            With<EnemyBullet>,
+        >,
+    ) {
+        for (bullet, lifetime) in bullets.iter() {
+            if lifetime.is_over() {
+                commands.entity(bullet).despawn_recursive();
+            }
+        }
+    }
+
+    // enemy bullets only collide with walls and the
+    // player, either way the bullet is used up
+    // (hurting the player is handled by health)
+    pub fn collide(
+        mut commands: Commands,
+        mut contact_events: EventReader<ContactEvent>,
+        bullets: Query<(), With<EnemyBullet>>,
+    ) {
+        for contact in contact_events.iter() {
+            if let ContactEvent::Started(h1, h2) = contact {
+                let (e1, e2) = (h1.entity(), h2.entity());
+                // no garunteed ordering so check both
+                for bullet in [e1, e2] {
+                    if bullets.get(bullet).is_ok() {
+                        commands
+                            .entity(bullet)
+                            .despawn_recursive();
+                    }
+                }
+            }
+        }
+    }
+}
+
+// all the components that make up
+// a bullet fired by an enemy
+#[derive(Bundle)]
+pub struct EnemyBulletBundle {
+    // how long the bullet exists for
+    lifetime: cooldown::Cooldown,
+    just_spawned: just_spawned::JustSpawned,
+    bullet: EnemyBullet,
+    damage: health::Damage,
+    sync: ColliderPositionSync,
+    #[bundle]
+    sprite: SpriteBundle,
+    #[bundle]
+    rigid_body: RigidBodyBundle,
+    #[bundle]
+    collider: ColliderBundle,
+}
+
+impl EnemyBulletBundle {
+    // creates a bullet from the given gun
+    // heading in direction dir
+    pub fn new(
+        gun: &super::EnemyGun,
+        dir: Vec2,
+        pos: Vec2,
+        materials: &asset::Materials,
+    ) -> Self {
+        let size = Vec2::splat(EnemyBullet::SIZE);
+        Self {
+            lifetime: cooldown::Cooldown::new(Some(
+                gun.lifetime,
+            )),
+            just_spawned: just_spawned::JustSpawned,
+            bullet: EnemyBullet,
+            damage: health::Damage {
+                team: health::Team::Enemy,
+                amount: gun.damage,
+            },
+            sync: ColliderPositionSync::Discrete,
+            sprite: SpriteBundle {
+                material: materials.enemy_bullet.clone(),
+                transform: Transform::from_translation(
+                    pos.extend(5.0),
+                ),
+                // hidden until the physics has
+                // put it in the right place
+                visible: Visible {
+                    is_visible: false,
+                    ..Default::default()
+                },
+                sprite: Sprite::new(size),
+                ..Default::default()
+            },
+            rigid_body: RigidBodyBundle {
+                position: pos.into(),
+                velocity: RigidBodyVelocity {
+                    linvel: (dir * gun.speed).into(),
+                    angvel: 0.0,
+                },
+                // ccd so we don't phase through walls
+                ccd: RigidBodyCcd {
+                    ccd_enabled: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            collider: ColliderBundle {
+                shape: ColliderShape::ball(size.x / 2.0),
+                flags: ColliderFlags {
+                    collision_groups:
+                        phys::masks::enemy_bullet(),
+                    solver_groups:
+                        phys::masks::enemy_bullet_solver(),
+                    active_events:
+                        ActiveEvents::CONTACT_EVENTS,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use crate::{asset, phys, state};
+use crate::{asset, enemies, phys, state};
 use bevy::prelude::*;
 use bevy_rapier2d::{na::Point2, prelude::*};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashSet,
     ops::{Index, IndexMut},
 };
 
@@ -64,38 +64,50 @@ fn leave_on_load(
     game_state.set(state::GameState::InLevel).unwrap();
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Grid {
     tiles: Vec<Vec<Tile>>,
+    size: GridSize,
     pub player: Option<GridPos>,
-    pub enemies: Vec<GridPos>,
+    // where each enemy spawns and what kind it is
+    pub enemies: Vec<(GridPos, enemies::Archetype)>,
 }
 
 impl Default for Grid {
-    // sets the grid to being completely empty
+    // sets the grid to being completely walls
+    // at the default size
     fn default() -> Self {
+        Self::new(GridSize::default())
+    }
+}
+
+impl Grid {
+    // the width and height of every tile
+    // in the game world
+    // (800px window / 20 tiles)
+    pub const TILE_SIZE: f32 = 40.0;
+
+    // creates a grid of the given size
+    // that is completely walls
+    pub fn new(size: GridSize) -> Self {
         Self {
             // tiles is a 2d array of Tile
+            // indexed by y then x
             tiles: vec![
-                vec![Tile::default(); Grid::HEIGHT];
-                Grid::WIDTH
+                vec![Tile::default(); size.width];
+                size.height
             ],
+            size,
             player: None,
             enemies: vec![],
         }
     }
-}
 
-impl Grid {
-    // this is the compile time
-    // garunteed height and width
-    // of the grid
-    pub const WIDTH: usize = 20;
-    pub const HEIGHT: usize = 20;
-    // whilst locking this at compile time
-    // reduces flexibility, it massively
-    // simplifies programming and speeds
-    // up performance hugely
+    // returns the number of tiles along
+    // each side of the grid
+    pub fn size(&self) -> GridSize {
+        self.size
+    }
 
     // this system spawns in the grid
     pub fn spawn(
@@ -108,11 +120,12 @@ impl Grid {
         commands.spawn_batch(
             // iterating over every position in the grid
             // and creating a new tile that corresponds to it
-            GridPos::iter_all()
+            GridPos::iter_all(grid.size())
                 .map(|pos| {
                     TileBundle::new(
                         &grid[pos],
                         &pos,
+                        grid.size(),
                         &*materials,
                     )
                 })
@@ -201,6 +214,7 @@ impl Grid {
                     if let Some(next_pos) = GridPos::try_new(
                         pos.x as isize + xd,
                         pos.y as isize + yd,
+                        self.size,
                     ) {
                         // returns false if this tile already scanned
                         // otherwise inserts and then pushes this pos
@@ -216,85 +230,6 @@ impl Grid {
         }
     }
 
-    // uses bfs to find a path between two points
-    // on the grid
-    pub fn path_between<T>(
-        &self,
-        start: GridPos,
-        end: GridPos,
-        mut filter: T,
-    ) -> Option<Vec<GridPos>>
-    where
-        T: FnMut(&Tile) -> bool,
-    {
-        // holds the current positions that are being searched
-        let mut current = vec![start];
-        // holds the next positions to be scanned
-        // (these are adjacent to the current positions)
-        let mut next = Vec::new();
-        // holds whether a given position has been scanned
-        let mut scanned = HashSet::new();
-        let mut origins = HashMap::new();
-        let mut found = false;
-        'outer: while !current.is_empty() {
-            // removes the elements of current and iterating over them
-            for pos in current.drain(0..) {
-                // if this tile should be filtered
-                // skip to next iteration
-                if !filter(&self[pos]) {
-                    continue;
-                }
-                // iterate over all the direct adjacent
-                // changes in x and y from our current
-                // position
-                for (xd, yd) in
-                    [(1, 0), (-1, 0), (0, 1), (0, -1)]
-                {
-                    // tries to create a new position offset
-                    // by xd and yd
-                    if let Some(next_pos) = GridPos::try_new(
-                        pos.x as isize + xd,
-                        pos.y as isize + yd,
-                    ) {
-                        // returns false if this tile already scanned
-                        // otherwise inserts and then pushes this pos
-                        // to the next positions and maps it's origin
-                        // as being from the current pos
-                        if scanned.insert(next_pos) {
-                            next.push(next_pos);
-                            origins.insert(next_pos, pos);
-                        }
-                    }
-                }
-                // if the end has been found exit
-                if pos == end {
-                    found = true;
-                    break 'outer;
-                }
-            }
-            // empties everthing in next into current
-            current.append(&mut next);
-        }
-        if found {
-            // creating a new vector to store the path
-            let mut path = Vec::new();
-            // starting from the end point
-            let mut current = end;
-            // trace the origin of the end to the start
-            while current != start {
-                path.push(current);
-                current = *origins.get(&current).unwrap()
-            }
-            path.push(current);
-            // then reverse the path to get it in the
-            // correct order
-            path.reverse();
-            Some(path)
-        } else {
-            None
-        }
-    }
-
     // checks at two pixel increments along the line from
     // start to end for anything not allowed by filter
     // and if anything is found returns false
@@ -314,9 +249,9 @@ impl Grid {
         T: FnMut(&Tile) -> bool,
     {
         // getting the world positon of the start
-        let mut pos = start.to_world();
+        let mut pos = start.to_world(self.size);
         // getting the world position of the end
-        let target = end.to_world();
+        let target = end.to_world(self.size);
         // getting the direction to travel from start to end
         let dir = (target - pos).normalize() * 2.0;
         // whilst the target hasn't been reached
@@ -328,7 +263,9 @@ impl Grid {
             // blocks the line of sight then
             // there it is blocked so return
             // early as false
-            if !filter(&self[GridPos::from_world(pos)]) {
+            if !filter(
+                &self[GridPos::from_world(pos, self.size)],
+            ) {
                 return false;
             }
         }
@@ -388,51 +325,59 @@ mod tile;
 pub use tile::{Tile, TileBundle, TileContent, TileSpawn};
 
 mod pos;
-pub use pos::GridPos;
+pub use pos::{GridPos, GridSize};
 
 mod generate;
 
+mod path;
+
 // provide a boundary around the edge
 // of the level to prevent physics objects going offscreen
 pub struct Walls;
 
 impl Walls {
-    pub fn spawn(mut commands: Commands) {
-        commands.spawn_bundle(ColliderBundle {
-            // creating a polyline colldier around
-            // the edge of the level
-            shape: ColliderShape::polyline(
-                // the verticies of the
-                // rectangle around the edge of the screen
-                vec![
-                    Point2::from_slice(&[
-                        -crate::WINDOW_WIDTH / 2.0,
-                        crate::WINDOW_HEIGHT / 2.0,
-                    ]),
-                    Point2::from_slice(&[
-                        crate::WINDOW_WIDTH / 2.0,
-                        crate::WINDOW_HEIGHT / 2.0,
+    pub fn spawn(mut commands: Commands, grid: Res<Grid>) {
+        // half the size of the level in the world
+        // as the level is centered on the origin
+        let half = grid.size().to_world() / 2.0;
+        commands
+            .spawn_bundle(ColliderBundle {
+                // creating a polyline colldier around
+                // the edge of the level
+                shape: ColliderShape::polyline(
+                    // the verticies of the
+                    // rectangle around the edge of the level
+                    vec![
+                        Point2::from_slice(&[
+                            -half.x, half.y,
+                        ]),
+                        Point2::from_slice(&[
+                            half.x, half.y,
+                        ]),
+                        Point2::from_slice(&[
+                            -half.x, -half.y,
+                        ]),
+                        Point2::from_slice(&[
+                            half.x, -half.y,
+                        ]),
+                    ],
+                    // indicating which order the indexes
+                    // should be read in to form a rectangle
+                    // (like the edges between each vertex)
+                    Some(vec![
+                        [0, 1],
+                        [1, 3],
+                        [3, 2],
+                        [2, 0],
                     ]),
-                    Point2::from_slice(&[
-                        -crate::WINDOW_WIDTH / 2.0,
-                        -crate::WINDOW_HEIGHT / 2.0,
-                    ]),
-                    Point2::from_slice(&[
-                        crate::WINDOW_WIDTH / 2.0,
-                        -crate::WINDOW_HEIGHT / 2.0,
-                    ]),
-                ],
-                // indicating which order the indexes
-                // should be read in to form a rectangle
-                // (like the edges between each vertex)
-                Some(vec![[0, 1], [1, 3], [3, 2], [2, 0]]),
-            ),
-            flags: ColliderFlags {
-                collision_groups: phys::masks::wall(),
+                ),
+                flags: ColliderFlags {
+                    collision_groups: phys::masks::wall(),
+                    ..Default::default()
+                },
                 ..Default::default()
-            },
-            ..Default::default()
-        });
+            })
+            .insert(Walls);
     }
 }
 
@@ -1,3 +1,4 @@
+use super::GridSize;
 use crate::state;
 use bevy::prelude::*;
 
@@ -15,6 +16,12 @@ impl Default for Difficulty {
 }
 
 impl Difficulty {
+    // starts the difficulty at a given level
+    // (for continuing a saved run)
+    pub fn at_level(level_num: u32) -> Self {
+        Self { level_num }
+    }
+
     // returns a copy of the level
     // num to prevent anything but
     // Difficulty's internal systems
@@ -37,6 +44,20 @@ impl Difficulty {
         -1000.0 / num.sqrt().sqrt() + 15.0 * num + 1300.0
     }
 
+    // returns how big the current level's grid is
+    // levels start the size of the window and then
+    // get wider and taller (up to a limit) as the camera
+    // can scroll around them
+    pub fn grid_size(&self) -> GridSize {
+        let base = GridSize::default();
+        let grow =
+            self.level_num.saturating_sub(1) as usize;
+        GridSize {
+            width: (base.width + grow * 2).min(36),
+            height: (base.height + grow).min(28),
+        }
+    }
+
     // increments the level_num whenever a level is cleared
     pub fn increment_level(
         mut difficulty: ResMut<Difficulty>,
@@ -1,9 +1,13 @@
 use super::{
-    Difficulty, Grid, GridPos, Tile, TileContent, TileSpawn,
+    Difficulty, Grid, GridPos, GridSize, Tile, TileContent,
+    TileSpawn,
+};
+use crate::{
+    enemies::Archetype,
+    seed::{GameRng, RngStream, RunSeed},
 };
 use bevy::prelude::*;
 use rand::{
-    rngs::ThreadRng,
     seq::{IteratorRandom, SliceRandom},
     Rng,
 };
@@ -20,16 +24,20 @@ struct GridRect {
 }
 
 impl GridRect {
-    pub const WHOLE_GRID: GridRect = GridRect {
-        start: GridPos::MIN,
-        end: GridPos::MAX,
-    };
+    // a rectangle covering every tile
+    // on a grid of the given size
+    pub fn whole(size: GridSize) -> GridRect {
+        GridRect {
+            start: GridPos::MIN,
+            end: size.max(),
+        }
+    }
 
     // creates a random rectangle
     // with min size min and max size max
     // that's inside inside
     pub fn random(
-        rng: &mut ThreadRng,
+        rng: &mut GameRng,
         min: GridPos,
         max: GridPos,
         inside: &GridRect,
@@ -69,19 +77,21 @@ impl Clearing {
     pub const CLEARING_RANGE: Range<usize> = 4..5;
 
     // creates a random clearing
-    pub fn random(rng: &mut ThreadRng) -> Clearing {
+    // on a grid of the given size
+    pub fn random(
+        rng: &mut GameRng,
+        size: GridSize,
+    ) -> Clearing {
         // create a random rect on the grid
         let clearing = GridRect::random(
             rng,
             GridPos::MIN + Self::CLEARING_MIN_SIZE,
-            GridPos::MAX - Self::CLEARING_MIN_SIZE,
-            &GridRect::WHOLE_GRID,
+            size.max() - Self::CLEARING_MIN_SIZE,
+            &GridRect::whole(size),
         );
         // the minimum size of an obstacle
-        let obstacle_min = GridPos::new(
-            Clearing::OBSTACLE_MIN_SIZE,
-            Clearing::OBSTACLE_MIN_SIZE,
-        );
+        let obstacle_min =
+            GridPos::MIN + Clearing::OBSTACLE_MIN_SIZE;
         // the maximum size of an obstacle
         let obstacle_max = clearing.end
             - clearing.start
@@ -135,25 +145,29 @@ impl Grid {
     pub fn level_generate(
         mut grid: ResMut<Grid>,
         difficulty: Res<Difficulty>,
+        seed: Res<RunSeed>,
     ) {
-        let mut rng = ThreadRng::default();
+        // each level gets its own rng from the run's
+        // seed so the same seed always gives the same level
+        let mut rng = seed
+            .rng(difficulty.level(), RngStream::Generation);
         *grid = Grid::generate(&mut rng, &*difficulty);
     }
 
     // adds the actual space to the level
-    fn add_clearings(&mut self, rng: &mut ThreadRng) {
+    fn add_clearings(&mut self, rng: &mut GameRng) {
         // creates a random clearing and then writes it
         // to the grid a random number of times
         for _ in 0..rng.gen_range(Clearing::CLEARING_RANGE)
         {
-            Clearing::random(rng).apply(self)
+            Clearing::random(rng, self.size).apply(self)
         }
     }
 
     // adds a player to the level
-    fn add_player(&mut self, rng: &mut ThreadRng) {
+    fn add_player(&mut self, rng: &mut GameRng) {
         // picks a random positon whose tile isn't a wall
-        self.player = GridPos::iter_all()
+        self.player = GridPos::iter_all(self.size)
             .filter(|pos| {
                 matches!(
                     self[*pos].cont,
@@ -174,8 +188,16 @@ impl Grid {
         let buffer = Self::PLAYER_SPAWN_BUFFER;
         // applying in a square around the player
         self.apply_in_area_mut(
-            GridPos::new_bounded(x - buffer, y - buffer),
-            GridPos::new_bounded(x + buffer, y + buffer),
+            GridPos::new_bounded(
+                x - buffer,
+                y - buffer,
+                self.size,
+            ),
+            GridPos::new_bounded(
+                x + buffer,
+                y + buffer,
+                self.size,
+            ),
             |tile| {
                 // if a tile is empty
                 // block spawning on it
@@ -215,7 +237,7 @@ impl Grid {
         // for better visuals
         self.apply_in_area_mut(
             GridPos::MIN,
-            GridPos::MAX,
+            self.size.max(),
             |tile| {
                 if tile.cont
                     == TileContent::Empty(
@@ -231,7 +253,7 @@ impl Grid {
     // adds enemies to the grid
     fn add_enemies(
         &mut self,
-        rng: &mut ThreadRng,
+        rng: &mut GameRng,
         difficulty: &Difficulty,
     ) {
         // initialising the enemy position store
@@ -242,7 +264,7 @@ impl Grid {
             .iter()
             // zipping with grid positions so that
             // can know position from tile
-            .zip(GridPos::iter_all())
+            .zip(GridPos::iter_all(self.size))
             // filters out tiles that aren't empty
             // and not already allocated for a spawn
             .filter(|(tile, _)| {
@@ -268,38 +290,45 @@ impl Grid {
         while points > 0.0 && !spawns.is_empty() {
             // getting a postiong for the enemy
             let pos = spawns.pop().unwrap();
+            // picking what kind of enemy to place out of
+            // the ones that can appear on this level
+            let archetype = *Archetype::ALL
+                .iter()
+                .filter(|archetype| {
+                    archetype.stats().min_level
+                        <= difficulty.level()
+                })
+                .choose(rng)
+                .unwrap();
             // base cost of an enemy placement in points
-            let mut cost = 300.0;
+            let base = archetype.stats().cost;
+            let mut cost = base;
+            // how far the enemy has to walk to the player
+            let distance = self
+                .path_between(
+                    self.player.unwrap(),
+                    pos,
+                    false,
+                    Tile::walk_cost,
+                )
+                .unwrap()
+                .cost;
             // for each square between the player and enemy
             // the enemy costs five points less to spawn in
-            cost -= 5.0
-                * self
-                    .path_between(
-                        self.player.unwrap(),
-                        pos,
-                        |tile| {
-                            matches!(
-                                tile.cont,
-                                TileContent::Empty(_)
-                            )
-                        },
-                    )
-                    .unwrap()
-                    .len() as f32;
+            // (+1 as the path's cost doesn't count the
+            // tile the player is standing on)
+            cost -= 5.0 * (distance + 1.0);
             // incase the cost becomes negative
-            // clamp it at 50.0 per enemy
-            if cost < 50.0 {
-                cost = 50.0;
+            // clamp it at a sixth of the base cost
+            if cost < base / 6.0 {
+                cost = base / 6.0;
             }
             // if the enemy can see the player
             // from their starting position
             // double their cost as this enemy
             // will be attacking immediately
             let filter = |tile: &Tile| {
-                matches!(
-                    tile.cont,
-                    TileContent::Empty(_)
-                )
+                matches!(tile.cont, TileContent::Empty(_))
             };
             if self.line_of_sight(
                 self.player.unwrap(),
@@ -310,15 +339,15 @@ impl Grid {
             }
             // if there's enough points to place this enemy
             // then charge that amount of points and record it's
-            // position
+            // position and kind
             if cost < points {
                 points -= cost;
-                enemies.push(pos);
+                enemies.push((pos, archetype));
             }
         }
         // marking all the tiles on the grid where
         // enemies will spawn as such
-        for pos in enemies.iter() {
+        for (pos, _) in enemies.iter() {
             self[*pos].cont =
                 TileContent::Empty(TileSpawn::Enemy)
         }
@@ -327,7 +356,7 @@ impl Grid {
     }
 
     pub fn generate(
-        rng: &mut ThreadRng,
+        rng: &mut GameRng,
         difficulty: &Difficulty,
     ) -> Grid {
         // calls continue if the expression passed
@@ -344,7 +373,8 @@ impl Grid {
         let mut grid;
         loop {
             // creating a new grid of entirely walls
-            grid = Grid::default();
+            // at the size this level should be
+            grid = Grid::new(difficulty.grid_size());
             // adding in clearings and the obstacles inside them
             grid.add_clearings(rng);
             // picking a spawn for the player
@@ -369,7 +399,7 @@ impl Grid {
                         )
                     })
                     .count()
-                    < (Grid::WIDTH * Grid::HEIGHT) / 3
+                    < grid.size().area() / 3
             );
             // spawning enemies on the grid
             grid.add_enemies(rng, difficulty);
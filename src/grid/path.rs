@@ -0,0 +1,371 @@
+use super::{Grid, GridPos, Tile, TileContent};
+use std::{
+    cmp::Ordering, collections::BinaryHeap,
+    f32::consts::SQRT_2,
+};
+
+// a route between two tiles on the grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    // every tile on the route including
+    // the start and the end
+    pub positions: Vec<GridPos>,
+    // the total cost of walking the route
+    pub cost: f32,
+}
+
+impl Tile {
+    // the default cost of walking onto a tile
+    // None means it can't be walked through at all
+    pub fn walk_cost(&self) -> Option<f32> {
+        match self.cont {
+            TileContent::Empty(_) => Some(1.0),
+            TileContent::Wall => None,
+        }
+    }
+}
+
+// a tile waiting to be searched by A*
+// ordered so that the binary heap (which pops
+// the biggest first) pops the cheapest estimate
+struct Node {
+    estimate: f32,
+    cost: f32,
+    pos: GridPos,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(
+        &self,
+        other: &Self,
+    ) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed so that smaller estimates come first
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl Grid {
+    // the changes in x and y to get to every
+    // neighbouring tile, the last four are diagonal
+    const NEIGHBOURS: [(isize, isize); 8] = [
+        (1, 0),
+        (-1, 0),
+        (0, 1),
+        (0, -1),
+        (1, 1),
+        (1, -1),
+        (-1, 1),
+        (-1, -1),
+    ];
+
+    // estimates the cost between two positions
+    // using the octile distance (the exact distance
+    // if every tile cost one and nothing was in the way)
+    fn octile(
+        a: GridPos,
+        b: GridPos,
+        diagonal: bool,
+    ) -> f32 {
+        let dx = (a.x as f32 - b.x as f32).abs();
+        let dy = (a.y as f32 - b.y as f32).abs();
+        if diagonal {
+            // going diagonally as much as possible
+            // and then straight for the rest
+            dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy)
+        } else {
+            dx + dy
+        }
+    }
+
+    // uses A* to find the cheapest path between two
+    // points on the grid
+    // cost gives the cost of walking onto a tile or None
+    // if it can't be walked through (costs should be at
+    // least one so the estimate never overshoots)
+    // diagonal allows moving diagonally but never
+    // squeezing between two blocked tiles
+    pub fn path_between<T>(
+        &self,
+        start: GridPos,
+        end: GridPos,
+        diagonal: bool,
+        mut cost: T,
+    ) -> Option<Path>
+    where
+        T: FnMut(&Tile) -> Option<f32>,
+    {
+        // if the start can't be stood on
+        // there's no path from it
+        cost(&self[start])?;
+        // flat arrays indexed by position are a lot
+        // cheaper than hashing every position
+        let size = self.size;
+        let index =
+            |pos: GridPos| pos.y * size.width + pos.x;
+        // the cheapest known cost to get to each tile
+        let mut best = vec![f32::INFINITY; size.area()];
+        // the tile each tile was cheapest to get to from
+        let mut origins = vec![None; size.area()];
+        // the tiles that still need to be searched
+        let mut open = BinaryHeap::new();
+        best[index(start)] = 0.0;
+        open.push(Node {
+            estimate: Self::octile(start, end, diagonal),
+            cost: 0.0,
+            pos: start,
+        });
+        while let Some(Node {
+            cost: so_far, pos, ..
+        }) = open.pop()
+        {
+            // the end has been reached so trace the
+            // origins back to the start
+            if pos == end {
+                let mut positions = vec![end];
+                let mut current = end;
+                while let Some(origin) =
+                    origins[index(current)]
+                {
+                    positions.push(origin);
+                    current = origin;
+                }
+                // the trace is from end to start
+                // so it needs flipping
+                positions.reverse();
+                return Some(Path {
+                    positions,
+                    cost: so_far,
+                });
+            }
+            // a cheaper way to this tile was found after
+            // this one was queued so skip it
+            if so_far > best[index(pos)] {
+                continue;
+            }
+            for &(xd, yd) in Self::NEIGHBOURS.iter() {
+                let is_diagonal = xd != 0 && yd != 0;
+                if is_diagonal && !diagonal {
+                    continue;
+                }
+                // tries to create a new position offset
+                // by xd and yd
+                let next = match GridPos::try_new(
+                    pos.x as isize + xd,
+                    pos.y as isize + yd,
+                    size,
+                ) {
+                    Some(next) => next,
+                    None => continue,
+                };
+                let step = match cost(&self[next]) {
+                    Some(step) => step,
+                    None => continue,
+                };
+                // a diagonal move can only be made if both
+                // tiles beside it are open, otherwise it
+                // would cut the corner of a wall
+                if is_diagonal {
+                    // the two tiles either side of the move
+                    let side_x = GridPos {
+                        x: next.x,
+                        y: pos.y,
+                    };
+                    let side_y = GridPos {
+                        x: pos.x,
+                        y: next.y,
+                    };
+                    if cost(&self[side_x]).is_none()
+                        || cost(&self[side_y]).is_none()
+                    {
+                        continue;
+                    }
+                }
+                // diagonal moves cover more distance
+                let length =
+                    if is_diagonal { SQRT_2 } else { 1.0 };
+                let next_cost = so_far + step * length;
+                if next_cost < best[index(next)] {
+                    best[index(next)] = next_cost;
+                    origins[index(next)] = Some(pos);
+                    open.push(Node {
+                        estimate: next_cost
+                            + Self::octile(
+                                next, end, diagonal,
+                            ),
+                        cost: next_cost,
+                        pos: next,
+                    });
+                }
+            }
+        }
+        // everything reachable was searched
+        // without finding the end
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{GridSize, TileSpawn};
+
+    // builds a grid from rows of characters where
+    // '#' is a wall, '~' is slow ground and anything
+    // else is open
+    fn grid(rows: &[&str]) -> Grid {
+        let mut grid = Grid::new(GridSize {
+            width: rows[0].len(),
+            height: rows.len(),
+        });
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid[GridPos { x, y }].cont = match c {
+                    '#' => TileContent::Wall,
+                    '~' => TileContent::Empty(
+                        TileSpawn::Blocked,
+                    ),
+                    _ => {
+                        TileContent::Empty(TileSpawn::None)
+                    }
+                };
+            }
+        }
+        grid
+    }
+
+    // walking onto slow ground costs ten times as much
+    fn weighted(tile: &Tile) -> Option<f32> {
+        match tile.cont {
+            TileContent::Empty(TileSpawn::Blocked) => {
+                Some(10.0)
+            }
+            _ => tile.walk_cost(),
+        }
+    }
+
+    fn pos(x: usize, y: usize) -> GridPos {
+        GridPos { x, y }
+    }
+
+    #[test]
+    fn no_squeezing_between_walls() {
+        let grid = grid(&[".#", "#."]);
+        assert_eq!(
+            grid.path_between(
+                pos(0, 0),
+                pos(1, 1),
+                true,
+                Tile::walk_cost,
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn goes_around_slow_ground() {
+        let grid = grid(&[".~~~.", "....."]);
+        let path = grid
+            .path_between(
+                pos(0, 0),
+                pos(4, 0),
+                false,
+                weighted,
+            )
+            .unwrap();
+        // down, along and back up is cheaper than
+        // wading straight through
+        assert_eq!(path.cost, 6.0);
+        assert!(path
+            .positions
+            .iter()
+            .all(|pos| grid[*pos].cont
+                != TileContent::Empty(TileSpawn::Blocked)));
+    }
+
+    #[test]
+    fn cost_matches_positions() {
+        let grid =
+            grid(&["..~..", ".#~#.", "....~", "~...."]);
+        let path = grid
+            .path_between(
+                pos(0, 0),
+                pos(4, 3),
+                true,
+                weighted,
+            )
+            .unwrap();
+        // adding up each step the same way the search does
+        let cost: f32 = path
+            .positions
+            .windows(2)
+            .map(|step| {
+                let length = if step[0].x != step[1].x
+                    && step[0].y != step[1].y
+                {
+                    SQRT_2
+                } else {
+                    1.0
+                };
+                weighted(&grid[step[1]]).unwrap() * length
+            })
+            .sum();
+        assert!((path.cost - cost).abs() < 1e-4);
+        assert_eq!(
+            path.positions.first(),
+            Some(&pos(0, 0))
+        );
+        assert_eq!(path.positions.last(), Some(&pos(4, 3)));
+    }
+
+    #[test]
+    fn walled_off_has_no_path() {
+        let grid = grid(&["..#..", "..#..", "..#.."]);
+        assert_eq!(
+            grid.path_between(
+                pos(0, 0),
+                pos(4, 2),
+                true,
+                Tile::walk_cost,
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn straight_lines_only_without_diagonals() {
+        let grid =
+            grid(&[".....", ".....", ".....", "....."]);
+        let path = grid
+            .path_between(
+                pos(0, 0),
+                pos(4, 3),
+                false,
+                Tile::walk_cost,
+            )
+            .unwrap();
+        assert!(path.positions.windows(2).all(|step| {
+            (step[0].x as isize - step[1].x as isize).abs()
+                + (step[0].y as isize - step[1].y as isize)
+                    .abs()
+                == 1
+        }));
+        assert_eq!(path.cost, 7.0);
+    }
+}
@@ -1,14 +1,14 @@
-use crate::grid;
+use crate::{grid, seed};
 use bevy::prelude::*;
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 use std::{
     convert::TryInto,
     ops::{Add, Sub},
 };
 
 // stores positions on the grid
-// garunteed to never be out of
-// the grid boundaries
+// the constructors check them against
+// the size of the grid they're for
 #[derive(
     Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash,
 )]
@@ -17,15 +17,50 @@ pub struct GridPos {
     pub y: usize,
 }
 
-impl GridPos {
-    // furthest position on the grid
+// the number of tiles along each side of a grid
+// this is decided when the grid is created so
+// levels can be different sizes
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct GridSize {
+    pub width: usize,
+    pub height: usize,
+}
+
+// the size every level used to be
+impl Default for GridSize {
+    fn default() -> Self {
+        Self {
+            width: 20,
+            height: 20,
+        }
+    }
+}
+
+impl GridSize {
+    // furthest position on a grid of this size
     // (bottom right)
-    pub const MAX: Self = Self {
-        x: grid::Grid::WIDTH - 1,
-        y: grid::Grid::HEIGHT - 1,
-        // -1 because vectors are 0
-        // indexed
-    };
+    pub fn max(&self) -> GridPos {
+        GridPos {
+            x: self.width - 1,
+            y: self.height - 1,
+            // -1 because vectors are 0
+            // indexed
+        }
+    }
+
+    // the total number of tiles in the grid
+    pub fn area(&self) -> usize {
+        self.width * self.height
+    }
+
+    // the size of the whole grid in the game world
+    pub fn to_world(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+            * grid::Grid::TILE_SIZE
+    }
+}
+
+impl GridPos {
     // origin position on the grid
     // (top left)
     pub const MIN: Self = Self { x: 0, y: 0 };
@@ -35,7 +70,11 @@ impl GridPos {
     // errors if over or under
     // T indicates it will work with any number
     // that can be cast into a unsigned interger
-    pub fn try_new<T>(x: T, y: T) -> Option<Self>
+    pub fn try_new<T>(
+        x: T,
+        y: T,
+        size: GridSize,
+    ) -> Option<Self>
     where
         T: TryInto<usize>,
     {
@@ -55,14 +94,18 @@ impl GridPos {
             };
         }
         Some(GridPos {
-            x: try_conv!(x =< GridPos::MAX.x),
-            y: try_conv!(y =< GridPos::MAX.y),
+            x: try_conv!(x =< size.max().x),
+            y: try_conv!(y =< size.max().y),
         })
     }
     // creates a new GridPos and if the indicies
     // passed are out of the grid bounds just rounds
     // to the closest edge
-    pub fn new_bounded<T>(x: T, y: T) -> Self
+    pub fn new_bounded<T>(
+        x: T,
+        y: T,
+        size: GridSize,
+    ) -> Self
     where
         T: TryInto<usize> + PartialOrd,
     {
@@ -95,25 +138,19 @@ impl GridPos {
             };
         }
         GridPos {
-            x: clamp!(x =< Self::MAX.x),
-            y: clamp!(y =< Self::MAX.y),
+            x: clamp!(x =< size.max().x),
+            y: clamp!(y =< size.max().y),
         }
     }
 
-    // conveinece function for creating
-    // GridPos when sure that its with valid
-    pub fn new<T>(x: T, y: T) -> Self
-    where
-        T: TryInto<usize>,
-    {
-        Self::try_new(x, y).unwrap()
-    }
-
-    // iterates over every position in the grid
-    pub fn iter_all() -> impl Iterator<Item = GridPos> {
-        (0..grid::Grid::HEIGHT)
-            .map(|y| {
-                (0..grid::Grid::WIDTH)
+    // iterates over every position in a grid
+    // of the given size
+    pub fn iter_all(
+        size: GridSize,
+    ) -> impl Iterator<Item = GridPos> {
+        (0..size.height)
+            .map(move |y| {
+                (0..size.width)
                     .map(move |x| GridPos { x, y })
             })
             .flatten()
@@ -123,56 +160,44 @@ impl GridPos {
 
     // converts a grid position to a
     // game world coordinate
-    pub fn to_world(&self) -> Vec2 {
+    pub fn to_world(&self, size: GridSize) -> Vec2 {
         Vec2::new(
             // x + 0.5 gets the center of the square
             (self.x as f32 + 0.5)
                 // multiplying by the width of a tile
-                * (crate::WINDOW_WIDTH / grid::Grid::WIDTH as f32)
-                // taking away half the screen res as the
-                // grids origin is top-left but the world
-                // coords origin is the center of the screen
-                - (crate::WINDOW_WIDTH / 2.0),
+                * grid::Grid::TILE_SIZE,
             // same reasons as above
-            (self.y as f32 + 0.5)
-                * (crate::WINDOW_WIDTH
-                    / grid::Grid::HEIGHT as f32)
-                - (crate::WINDOW_HEIGHT / 2.0),
+            (self.y as f32 + 0.5) * grid::Grid::TILE_SIZE,
         )
+            // taking away half the grid's size as the
+            // grids origin is top-left but the world
+            // coords origin is the center of the grid
+            - size.to_world() / 2.0
     }
 
     // takes a vector and returns the grid
     // positon of that vector
-    pub fn from_world(pos: Vec2) -> GridPos {
-        // converts v, a vector dimension, into a
-        // usize given the dimension of the total
-        // window and the width of the total grid
-        macro_rules! to_grid {
-            ($v:expr, $wndw:expr, $grid: expr) => {
-                ((($v + ($wndw / 2.0)) / $wndw)
-                    * $grid as f32)
-                    .round() as usize
-            };
-        }
-        GridPos::try_new(
-            to_grid!(
-                pos.x,
-                crate::WINDOW_WIDTH,
-                grid::Grid::WIDTH
-            ),
-            to_grid!(
-                pos.y,
-                crate::WINDOW_HEIGHT,
-                grid::Grid::HEIGHT
-            ),
+    // (anything off the grid goes to the closest edge)
+    pub fn from_world(
+        pos: Vec2,
+        size: GridSize,
+    ) -> GridPos {
+        // moving the origin back to the top left
+        // and then counting how many tiles along it is
+        let tiles = ((pos + size.to_world() / 2.0)
+            / grid::Grid::TILE_SIZE)
+            .floor();
+        GridPos::new_bounded(
+            tiles.x as isize,
+            tiles.y as isize,
+            size,
         )
-        .unwrap_or_else(|| GridPos::new(0, 0))
     }
 
     // generates a new random grid position
     // inside the specfied range
     pub fn random(
-        rng: &mut ThreadRng,
+        rng: &mut seed::GameRng,
         min: GridPos,
         max: GridPos,
     ) -> Self {
@@ -187,7 +212,10 @@ impl GridPos {
 impl Add for GridPos {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
-        GridPos::new(self.x + rhs.x, self.y + rhs.y)
+        GridPos {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
     }
 }
 
@@ -195,7 +223,10 @@ impl Add for GridPos {
 impl Sub for GridPos {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
-        GridPos::new(self.x - rhs.x, self.y - rhs.y)
+        GridPos {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
     }
 }
 
@@ -203,7 +234,10 @@ impl Sub for GridPos {
 impl Add<usize> for GridPos {
     type Output = Self;
     fn add(self, rhs: usize) -> Self::Output {
-        GridPos::new(self.x + rhs, self.y + rhs)
+        GridPos {
+            x: self.x + rhs,
+            y: self.y + rhs,
+        }
     }
 }
 
@@ -211,6 +245,9 @@ impl Add<usize> for GridPos {
 impl Sub<usize> for GridPos {
     type Output = Self;
     fn sub(self, rhs: usize) -> Self::Output {
-        GridPos::new(self.x - rhs, self.y - rhs)
+        GridPos {
+            x: self.x - rhs,
+            y: self.y - rhs,
+        }
     }
 }
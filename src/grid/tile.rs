@@ -1,10 +1,10 @@
-use super::GridPos;
+use super::{GridPos, GridSize};
 use crate::{asset, grid, phys};
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 // represents one square on the grid
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Tile {
     pub cont: TileContent,
 }
@@ -55,17 +55,15 @@ impl TileBundle {
     pub fn new(
         tile: &Tile,
         pos: &GridPos,
+        size: GridSize,
         materials: &asset::Materials,
     ) -> Self {
         Self {
             tile: tile.clone(),
             sprite: SpriteBundle {
-                // giving a sprite sized relative to the window
-                sprite: Sprite::new(Vec2::new(
-                    crate::WINDOW_WIDTH
-                        / super::Grid::WIDTH as f32,
-                    crate::WINDOW_HEIGHT
-                        / super::Grid::HEIGHT as f32,
+                // giving a sprite the size of a tile
+                sprite: Sprite::new(Vec2::splat(
+                    grid::Grid::TILE_SIZE,
                 )),
                 // material means color so im matching
                 // against Wall and Empty for different
@@ -82,7 +80,7 @@ impl TileBundle {
                 // on the screen with its grid position
                 // translated into a world coordinate
                 transform: Transform::from_translation(
-                    pos.to_world().extend(0.0),
+                    pos.to_world(size).extend(0.0),
                 ),
 
                 ..Default::default()
@@ -97,14 +95,10 @@ impl TileBundle {
                 // sprite but rapier's cuboids are measured
                 // by extent which is half width/height
                 shape: ColliderShape::cuboid(
-                    crate::WINDOW_HEIGHT
-                        / grid::Grid::HEIGHT as f32
-                        / 2.0,
-                    crate::WINDOW_WIDTH
-                        / grid::Grid::WIDTH as f32
-                        / 2.0,
+                    grid::Grid::TILE_SIZE / 2.0,
+                    grid::Grid::TILE_SIZE / 2.0,
                 ),
-                position: pos.to_world().into(),
+                position: pos.to_world(size).into(),
                 flags: ColliderFlags {
                     collision_groups: match tile.cont {
                         // if the tile is empty it collides with nothing
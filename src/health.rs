@@ -0,0 +1,167 @@
+use crate::{clock, state};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(state::GameState::InLevel)
+                .with_system(Health::take_damage.system())
+                .with_system(Health::recover.system()),
+        );
+    }
+}
+
+// which side something is on so that
+// things don't hurt their own side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Team {
+    Player,
+    Enemy,
+}
+
+// how much damage something can take before it dies
+#[derive(Debug, Clone)]
+pub struct Health {
+    team: Team,
+    hp: f32,
+    // how many seconds something can't be hurt
+    // for after getting hit
+    iframes: f32,
+    // how many of those seconds are left
+    invulnerable: f32,
+    // everything that does damage that's
+    // touching this right now
+    touching: Vec<Entity>,
+}
+
+// attach to anything that hurts things on the
+// other team when it touches them
+#[derive(Debug, Clone)]
+pub struct Damage {
+    pub team: Team,
+    pub amount: f32,
+}
+
+impl Health {
+    // creates a full health pool that is
+    // invulnerable for iframes seconds after each hit
+    pub fn new(team: Team, hp: f32, iframes: f32) -> Self {
+        Self {
+            team,
+            hp,
+            iframes,
+            invulnerable: 0.0,
+            touching: Vec::new(),
+        }
+    }
+
+    // whether all the health has been used up
+    pub fn is_dead(&self) -> bool {
+        self.hp <= 0.0
+    }
+
+    // hurts this by damage if it isn't invulnerable
+    // and the damage comes from the other team
+    // returning whether it was hurt
+    pub fn hit(&mut self, damage: &Damage) -> bool {
+        if damage.team == self.team
+            || self.invulnerable > 0.0
+            || self.is_dead()
+        {
+            return false;
+        }
+        self.hp -= damage.amount;
+        self.invulnerable = self.iframes;
+        true
+    }
+
+    // can't be hurt for at least the next secs seconds
+    pub fn make_invulnerable(&mut self, secs: f32) {
+        self.invulnerable = self.invulnerable.max(secs);
+    }
+
+    // applies damage whenever something that does
+    // damage is touching something with health, so
+    // staying pressed against something keeps hurting
+    // it once its invulnerability runs out
+    fn take_damage(
+        mut contact_events: EventReader<ContactEvent>,
+        mut healths: Query<&mut Health>,
+        damages: Query<&Damage>,
+    ) {
+        // keeping track of what's touching what
+        for contact in contact_events.iter() {
+            let (h1, h2, started) = match contact {
+                ContactEvent::Started(h1, h2) => {
+                    (h1, h2, true)
+                }
+                ContactEvent::Stopped(h1, h2) => {
+                    (h1, h2, false)
+                }
+            };
+            let (e1, e2) = (h1.entity(), h2.entity());
+            // no garunteed ordering so check both ways
+            // (both could hurt each other)
+            for (hurt, hurter) in [(e1, e2), (e2, e1)] {
+                if damages.get(hurter).is_err() {
+                    continue;
+                }
+                if let Ok(mut health) =
+                    healths.get_mut(hurt)
+                {
+                    if !started {
+                        health.touching.retain(|other| {
+                            *other != hurter
+                        });
+                    } else if !health
+                        .touching
+                        .contains(&hurter)
+                    {
+                        health.touching.push(hurter);
+                    }
+                }
+            }
+        }
+        for mut health in healths.iter_mut() {
+            // not mutably borrowing it unless it's touching
+            // something so it's not marked as changed
+            if health.touching.is_empty() {
+                continue;
+            }
+            let mut touching =
+                std::mem::take(&mut health.touching);
+            // forgetting anything that's been despawned
+            touching.retain(|hurter| {
+                match damages.get(*hurter) {
+                    Ok(damage) => {
+                        health.hit(damage);
+                        true
+                    }
+                    Err(_) => false,
+                }
+            });
+            health.touching = touching;
+        }
+    }
+
+    // counts down invulnerability and makes things
+    // flicker whilst they can't be hurt
+    fn recover(
+        clock: Res<clock::Clock>,
+        mut query: Query<(&mut Health, &mut Visible)>,
+    ) {
+        for (mut health, mut visible) in query.iter_mut() {
+            if health.invulnerable <= 0.0 {
+                continue;
+            }
+            health.invulnerable -= clock.delta_seconds();
+            // on and off ten times a second
+            visible.is_visible = health.invulnerable <= 0.0
+                || (health.invulnerable * 10.0).fract()
+                    < 0.5;
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{
+    asset, cooldown, enemies, grid, item, player, state,
+};
+use bevy::prelude::{DespawnRecursiveExt, *};
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_enter(state::GameState::InLevel)
+                .with_system(Hud::spawn.system()),
+        )
+        // always running so the hud is still filled in
+        // whilst the countdown and pause menu are on top
+        .add_system(Hud::lives.system())
+        .add_system(Hud::level.system())
+        .add_system(Hud::enemies.system())
+        .add_system(Hud::reload.system())
+        .add_system(Hud::items.system())
+        .add_system(
+            state::GameState::despawn::<Hud>(
+                state::GameState::InLevel,
+            ),
+        );
+    }
+}
+
+// the bar along the top of the screen
+// that shows how the level is going
+pub struct Hud;
+
+// the parts of the hud that get filled in
+// whenever what they show changes
+struct HudLives;
+struct HudLevel;
+struct HudEnemies;
+struct HudReload;
+struct HudItems;
+
+impl Hud {
+    // how tall the bar is
+    const HEIGHT: f32 = 36.0;
+    // how big each life and item icon is
+    const ICON_SIZE: f32 = 20.0;
+    // how long the reload bar is when full
+    const RELOAD_WIDTH: f32 = 100.0;
+
+    // spawns in the empty hud which is filled in
+    // by the other systems once it's been added
+    fn spawn(
+        mut commands: Commands,
+        materials: Res<asset::Materials>,
+    ) {
+        let text = || TextBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: materials.main_font.clone(),
+                    font_size: 16.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                Default::default(),
+            ),
+            style: Style {
+                margin: Rect::all(Val::Px(8.0)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // a row of icons that gets refilled
+        let row = || NodeBundle {
+            style: Style {
+                margin: Rect::all(Val::Px(8.0)),
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: materials.menu_overlay.clone(),
+            ..Default::default()
+        };
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(
+                        Val::Percent(100.0),
+                        Val::Px(Self::HEIGHT),
+                    ),
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(0.0),
+                        top: Val::Px(0.0),
+                        ..Default::default()
+                    },
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: materials.menu_overlay.clone(),
+                ..Default::default()
+            })
+            .insert(Hud)
+            .with_children(|parent| {
+                parent.spawn_bundle(row()).insert(HudLives);
+                parent
+                    .spawn_bundle(text())
+                    .insert(HudLevel);
+                parent
+                    .spawn_bundle(text())
+                    .insert(HudEnemies);
+                // the reload bar fills up from empty
+                // to full as the gun cools down
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(
+                                Val::Px(Self::RELOAD_WIDTH),
+                                Val::Px(8.0),
+                            ),
+                            margin: Rect::all(Val::Px(8.0)),
+                            ..Default::default()
+                        },
+                        material: materials
+                            .button_normal
+                            .clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    size: Size::new(
+                                        Val::Percent(100.0),
+                                        Val::Percent(100.0),
+                                    ),
+                                    ..Default::default()
+                                },
+                                material: materials
+                                    .player_gun
+                                    .clone(),
+                                ..Default::default()
+                            })
+                            .insert(HudReload);
+                    });
+                parent.spawn_bundle(row()).insert(HudItems);
+            });
+    }
+
+    // shows a square for each life the player has left
+    fn lives(
+        mut commands: Commands,
+        materials: Res<asset::Materials>,
+        lives: Res<player::Lives>,
+        added: Query<(), Added<Hud>>,
+        rows: Query<
+            (Entity, Option<&Children>),
+            With<HudLives>,
+        >,
+    ) {
+        if !lives.is_changed()
+            && added.iter().next().is_none()
+        {
+            return;
+        }
+        for (entity, children) in rows.iter() {
+            Hud::clear(&mut commands, children);
+            commands.entity(entity).with_children(
+                |parent| {
+                    for _ in 0..lives.0 {
+                        parent.spawn_bundle(Hud::icon(
+                            materials.player_body.clone(),
+                        ));
+                    }
+                },
+            );
+        }
+    }
+
+    // shows which level the player is on
+    fn level(
+        difficulty: Res<grid::Difficulty>,
+        added: Query<(), Added<Hud>>,
+        mut texts: Query<&mut Text, With<HudLevel>>,
+    ) {
+        if !difficulty.is_changed()
+            && added.iter().next().is_none()
+        {
+            return;
+        }
+        for mut text in texts.iter_mut() {
+            text.sections[0].value =
+                format!("Level {}", difficulty.level());
+        }
+    }
+
+    // counts the enemies left, only rewriting the text
+    // when the count is different to what's shown
+    // (enemies are despawned too late in the frame for
+    // their removal to be seen here)
+    fn enemies(
+        added: Query<(), Added<Hud>>,
+        remaining: Query<(), With<enemies::Enemy>>,
+        mut shown: Local<Option<usize>>,
+        mut texts: Query<&mut Text, With<HudEnemies>>,
+    ) {
+        let count = remaining.iter().count();
+        if added.iter().next().is_none()
+            && *shown == Some(count)
+        {
+            return;
+        }
+        *shown = Some(count);
+        for mut text in texts.iter_mut() {
+            text.sections[0].value =
+                format!("Enemies {}", count);
+        }
+    }
+
+    // fills the reload bar as the gun's cooldown ticks
+    fn reload(
+        guns: Query<
+            &cooldown::Cooldown,
+            (
+                With<player::Gun>,
+                Changed<cooldown::Cooldown>,
+            ),
+        >,
+        mut bars: Query<&mut Style, With<HudReload>>,
+    ) {
+        let cooldown = match guns.single() {
+            Ok(cooldown) => cooldown,
+            Err(_) => return,
+        };
+        for mut style in bars.iter_mut() {
+            style.size.width =
+                Val::Percent(cooldown.progress() * 100.0);
+        }
+    }
+
+    // shows an icon for each kind of item the
+    // player has with how many of it they have
+    fn items(
+        mut commands: Commands,
+        materials: Res<asset::Materials>,
+        items: Res<item::ItemManager>,
+        config: Res<item::Config>,
+        catalogue: Res<item::ItemCatalogue>,
+        added: Query<(), Added<Hud>>,
+        rows: Query<
+            (Entity, Option<&Children>),
+            With<HudItems>,
+        >,
+    ) {
+        // the config is what's rebuilt when
+        // the items are applied to the level
+        if !config.is_changed()
+            && added.iter().next().is_none()
+        {
+            return;
+        }
+        let mut listed = Vec::new();
+        for id in items.ids() {
+            if !listed.contains(id) {
+                listed.push(id.clone());
+            }
+        }
+        for (entity, children) in rows.iter() {
+            Hud::clear(&mut commands, children);
+            commands.entity(entity).with_children(|parent| {
+                for id in listed.iter() {
+                    let count = config.flags.count(id);
+                    parent
+                        .spawn_bundle(Hud::icon(
+                            materials.button_normal.clone(),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn_bundle(TextBundle {
+                                text: Text::with_section(
+                                    if *count > 1 {
+                                        format!(
+                                            "{}{}",
+                                            catalogue.icon(id),
+                                            count
+                                        )
+                                    } else {
+                                        catalogue.icon(id).to_string()
+                                    },
+                                    TextStyle {
+                                        font: materials
+                                            .main_font
+                                            .clone(),
+                                        font_size: 10.0,
+                                        color: Color::rgb(
+                                            0.9, 0.9, 0.9,
+                                        ),
+                                    },
+                                    Default::default(),
+                                ),
+                                ..Default::default()
+                            });
+                        });
+                }
+            });
+        }
+    }
+
+    // a small square for the rows of icons
+    fn icon(material: Handle<ColorMaterial>) -> NodeBundle {
+        NodeBundle {
+            style: Style {
+                size: Size::new(
+                    Val::Px(Self::ICON_SIZE),
+                    Val::Px(Self::ICON_SIZE),
+                ),
+                margin: Rect::all(Val::Px(2.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material,
+            ..Default::default()
+        }
+    }
+
+    // removes the old icons before a row is refilled
+    fn clear(
+        commands: &mut Commands,
+        children: Option<&Children>,
+    ) {
+        for child in
+            children.into_iter().flat_map(|c| c.iter())
+        {
+            commands.entity(*child).despawn_recursive();
+        }
+    }
+}
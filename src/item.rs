@@ -4,8 +4,14 @@ pub struct ItemPlugin;
 
 impl Plugin for ItemPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.init_resource::<Config>()
+        app.add_asset::<ItemDefs>()
+            .init_asset_loader::<ItemDefsLoader>()
+            .init_resource::<ItemCatalogue>()
+            .init_resource::<Config>()
             .init_resource::<ItemManager>()
+            .init_resource::<LootTable>()
+            // keeping the items up to date with the file
+            .add_system(ItemCatalogue::sync.system())
             // applying the items at the same time as
             // generating the level
             .add_system(
@@ -30,48 +36,98 @@ pub struct ItemManager {
 impl ItemManager {
     pub fn apply(world: &mut World) {
         // creating a new default config
-        let mut config = Config::from_world(world);
-        // generating a new store of items
-        let mut flags = ConfigFlags::new();
-        // fetching the list of TtemIds the player has
+        let config = Config::from_world(world);
+        // working out what the player's items do
         let ItemManager { item_ids } =
             world.get_resource::<ItemManager>().unwrap();
+        let catalogue =
+            world.get_resource::<ItemCatalogue>().unwrap();
+        let mut config =
+            Self::configure(config, item_ids, catalogue);
+        for (id, _) in config.flags.iter() {
+            // it's been taken out of the items file
+            if catalogue.get(id).is_none() {
+                warn!("Unknown item: {}", id.as_str());
+            }
+        }
+        // copying the items so the world can be
+        // used again for misc
+        let items = catalogue
+            .defs()
+            .iter()
+            .filter(|def| *config.flags.count(&def.id) > 0)
+            .cloned()
+            .collect::<Vec<_>>();
+        for def in items.iter() {
+            // since misc is only called once no need to
+            // take into account the count of an item
+            def.misc(&mut config, world);
+        }
+        // adding the config to the world
+        // overwriting the old one
+        world.insert_resource(config)
+    }
+
+    // works out what the given items do to config
+    // (everything but misc, which needs the world)
+    pub fn configure(
+        mut config: Config,
+        item_ids: &[ItemId],
+        catalogue: &ItemCatalogue,
+    ) -> Config {
+        // generating a new store of items
+        let mut flags = ConfigFlags::new();
         // adding every player item to the flags
         for id in item_ids.iter().cloned() {
             flags.add(id)
         }
-        // converting all the ItemIds to Box<dyn Items>
-        // so i can Item methods on them
-        let items = flags
+        // looking up what every item does in the order
+        // they're written in the items file so the
+        // same items always come out the same way
+        let items = catalogue
+            .defs()
+            .iter()
+            .filter(|def| *flags.count(&def.id) > 0)
+            .map(|def| (def, *flags.count(&def.id)))
+            .collect::<Vec<_>>();
+        // and every synergy the items complete
+        // in the order they're written
+        let synergies = catalogue
+            .synergies()
             .iter()
-            .map(|(id, count)| (id.to_item(), *count))
+            .filter(|synergy| synergy.is_complete(&flags))
             .collect::<Vec<_>>();
-        // fancy macro that looks confusing but reduces code size
-        // slightly
-        // takes in two methods of item and calls the
-        // first on every first occurence of a given item
-        // and the second on every subsequent occurence
-        macro_rules! apply {
-            ($first:expr => $otherwise:expr) => {
-                for (item, count) in items.iter() {
-                    // calling the first method
-                    $first(&**item, &mut config);
-                    // repeating as many times as there
-                    // are items - 1 as i have already
-                    // called first
-                    for _ in 0..count - 1 {
-                        $otherwise(&**item, &mut config);
-                    }
-                }
-            };
+        // adding for every item before multiplying so the
+        // order the items were picked up doesn't matter
+        // with synergies going after the items
+        for (def, count) in items.iter() {
+            def.add(&mut config, *count);
         }
-        apply!(Item::add_first => Item::add);
-        apply!(Item::mul_first => Item::mul);
-        // doing misc the not fancy way as its a bit simpler
-        for (item, _) in items.iter() {
-            // since misc is only called once no need to
-            // take into account the count of an item
-            item.misc(&mut config, world, &flags)
+        for synergy in synergies.iter() {
+            synergy.add(&mut config);
+        }
+        for (def, count) in items.iter() {
+            def.mul(&mut config, *count);
+        }
+        for synergy in synergies.iter() {
+            synergy.mul(&mut config);
+        }
+        // turning on every behaviour before any of them
+        // are applied so they know about each other
+        let behaviours =
+            items
+                .iter()
+                .flat_map(|(def, _)| def.flags.iter())
+                .chain(synergies.iter().flat_map(
+                    |synergy| synergy.flags.iter(),
+                ));
+        for flag in behaviours {
+            flags.add_flag(*flag);
+        }
+        for flag in Flag::ALL.iter() {
+            if flags.contains(*flag) {
+                flag.apply(&mut config, &flags);
+            }
         }
         // limiting the configs values in a range
         // so that no weird / buggy behaviour happens
@@ -79,9 +135,7 @@ impl ItemManager {
         // setting configs flags to the flags
         // we've already computed
         config.flags = flags;
-        // adding the config to the world
-        // overwriting the old one
-        world.insert_resource(config)
+        config
     }
 
     // resets the player's items when the game is over
@@ -96,12 +150,15 @@ impl ItemManager {
         }
     }
 
-    pub fn list(&self) -> String {
-        self.item_ids
-            .iter()
-            .map(|id| id.to_item().name())
-            .collect::<Vec<_>>()
-            .join(", ")
+    // creates a manager already holding the given items
+    // (for continuing a saved run)
+    pub fn from_ids(item_ids: Vec<ItemId>) -> Self {
+        Self { item_ids }
+    }
+
+    // every item the player has in the order they got them
+    pub fn ids(&self) -> &[ItemId] {
+        &self.item_ids
     }
 
     // gives the player this item
@@ -115,3 +172,86 @@ pub use config::*;
 
 mod items;
 pub use items::*;
+
+// loading the items from the items file
+mod catalogue;
+pub use catalogue::ItemCatalogue;
+use catalogue::{ItemDefs, ItemDefsLoader};
+
+// what the item menu offers
+mod loot;
+pub use loot::{LootTable, Rarity};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset;
+
+    // the game's colors, which the config holds
+    // on to but never looks at here
+    fn materials() -> asset::Materials {
+        let mut app = App::build();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(bevy::asset::AssetPlugin)
+            .add_asset::<ColorMaterial>()
+            .add_asset::<Font>()
+            .init_resource::<asset::Materials>();
+        app.world_mut()
+            .remove_resource::<asset::Materials>()
+            .unwrap()
+    }
+
+    // two items that both change the damage and
+    // a synergy between them that changes it again
+    fn catalogue() -> ItemCatalogue {
+        ItemCatalogue::parse(
+            r#"(
+                items: [
+                    (id: "A", name: "A", desc: "", icon: "A",
+                        rarity: Common, flags: [Bouncy],
+                        add: {"gun.damage": 0.5},
+                        mul: {"gun.damage": 2.0}),
+                    (id: "B", name: "B", desc: "", icon: "B",
+                        rarity: Common,
+                        add: {"gun.damage": 0.5},
+                        mul: {"gun.speed": 1.2}),
+                ],
+                synergies: [
+                    (name: "AB", desc: "", requires: ["A", "B"],
+                        flags: [Ricochet],
+                        add: {"gun.damage": 1.0},
+                        mul: {"gun.damage": 1.5}),
+                ],
+            )"#,
+        )
+    }
+
+    #[test]
+    fn pickup_order_doesnt_matter() {
+        let materials = materials();
+        let catalogue = catalogue();
+        let ids = catalogue
+            .defs()
+            .iter()
+            .map(|def| def.id.clone())
+            .collect::<Vec<_>>();
+        let mut reversed = ids.clone();
+        reversed.reverse();
+        let forwards = ItemManager::configure(
+            Config::new(&materials),
+            &ids,
+            &catalogue,
+        );
+        let backwards = ItemManager::configure(
+            Config::new(&materials),
+            &reversed,
+            &catalogue,
+        );
+        assert_eq!(forwards.stats(), backwards.stats());
+        // every add (the synergy's last) before
+        // every multiply (the synergy's last)
+        assert_eq!(forwards.gun.damage, 9.0);
+        assert!(forwards.flags.contains(Flag::Ricochet));
+        assert!(backwards.flags.contains(Flag::Ricochet));
+    }
+}
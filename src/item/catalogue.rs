@@ -0,0 +1,191 @@
+use super::{ItemDef, ItemId, LootTable, Synergy};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+// every item and synergy as they're
+// written in the items file
+#[derive(Debug, Deserialize, TypeUuid)]
+#[uuid = "6f0e3b1c-8a55-4d2e-9b7a-2c41d7f9e310"]
+pub struct ItemDefs {
+    items: Vec<ItemDef>,
+    #[serde(default)]
+    synergies: Vec<Synergy>,
+}
+
+impl ItemDefs {
+    // makes sure every item makes sense before
+    // any of them are used
+    fn validate(&self) -> Result<(), String> {
+        for (i, def) in self.items.iter().enumerate() {
+            def.validate()?;
+            if self.items[..i]
+                .iter()
+                .any(|other| other.id == def.id)
+            {
+                return Err(format!(
+                    "item {} is defined twice",
+                    def.id.as_str()
+                ));
+            }
+        }
+        for synergy in self.synergies.iter() {
+            synergy.validate(&self.items)?;
+        }
+        Ok(())
+    }
+}
+
+// reads the items file
+#[derive(Default)]
+pub struct ItemDefsLoader;
+
+impl AssetLoader for ItemDefsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let defs: ItemDefs =
+                ron::de::from_bytes(bytes)?;
+            // a broken file fails to load so the items
+            // from before a hot reload are kept instead
+            defs.validate().map_err(anyhow::Error::msg)?;
+            load_context
+                .set_default_asset(LoadedAsset::new(defs));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+// the items that are in the game, kept up to date
+// with the items file (even whilst the game's running)
+pub struct ItemCatalogue {
+    handle: Handle<ItemDefs>,
+    defs: Vec<ItemDef>,
+    synergies: Vec<Synergy>,
+}
+
+impl FromWorld for ItemCatalogue {
+    // starts loading the items file
+    fn from_world(world: &mut World) -> Self {
+        let asset_server =
+            world.get_resource::<AssetServer>().unwrap();
+        Self {
+            handle: asset_server.load(Self::PATH),
+            defs: Vec::new(),
+            synergies: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl ItemCatalogue {
+    // a catalogue written out in a test in the same
+    // format as the items file instead of loaded
+    pub fn parse(text: &str) -> Self {
+        let defs: ItemDefs = ron::from_str(text).unwrap();
+        defs.validate().unwrap();
+        Self {
+            handle: Handle::default(),
+            defs: defs.items,
+            synergies: defs.synergies,
+        }
+    }
+}
+
+impl ItemCatalogue {
+    // where the items are in the assets folder
+    const PATH: &'static str = "items.ron";
+
+    // the handle to wait on whilst loading
+    pub fn handle(&self) -> &Handle<ItemDefs> {
+        &self.handle
+    }
+
+    // every item in the order they're written
+    pub fn defs(&self) -> &[ItemDef] {
+        &self.defs
+    }
+
+    // every synergy in the order they're applied
+    pub fn synergies(&self) -> &[Synergy] {
+        &self.synergies
+    }
+
+    // the synergies that taking the item would finish
+    // off given the items already taken
+    pub fn completed_by<'a>(
+        &'a self,
+        id: &'a ItemId,
+        taken: &'a [ItemId],
+    ) -> impl Iterator<Item = &'a Synergy> {
+        self.synergies.iter().filter(move |synergy| {
+            synergy.requires.contains(id)
+                && !taken.contains(id)
+                && synergy.requires.iter().all(|required| {
+                    required == id
+                        || taken.contains(required)
+                })
+        })
+    }
+
+    // looks up an item by its id
+    pub fn get(&self, id: &ItemId) -> Option<&ItemDef> {
+        self.defs.iter().find(|def| def.id == *id)
+    }
+
+    // the name of an item or its id if it's
+    // been taken out of the items file
+    pub fn name<'a>(&'a self, id: &'a ItemId) -> &'a str {
+        self.get(id).map_or(id.as_str(), |def| &def.name)
+    }
+
+    // the icon of an item or its id if it's
+    // been taken out of the items file
+    pub fn icon<'a>(&'a self, id: &'a ItemId) -> &'a str {
+        self.get(id).map_or(id.as_str(), |def| &def.icon)
+    }
+
+    // copies the items out of the file whenever it's
+    // (re)loaded and rebuilds what's offered from them
+    // (changes to the items the player has
+    // take effect from the next level)
+    pub fn sync(
+        mut catalogue: ResMut<ItemCatalogue>,
+        mut table: ResMut<LootTable>,
+        mut events: EventReader<AssetEvent<ItemDefs>>,
+        assets: Res<Assets<ItemDefs>>,
+    ) {
+        for event in events.iter() {
+            let handle = match event {
+                AssetEvent::Created { handle }
+                | AssetEvent::Modified { handle } => handle,
+                AssetEvent::Removed { .. } => continue,
+            };
+            if *handle != catalogue.handle {
+                continue;
+            }
+            if let Some(defs) = assets.get(handle) {
+                info!(
+                    "Loaded {} items and {} synergies",
+                    defs.items.len(),
+                    defs.synergies.len()
+                );
+                catalogue.defs = defs.items.clone();
+                catalogue.synergies =
+                    defs.synergies.clone();
+                *table = LootTable::new(&catalogue);
+            }
+        }
+    }
+}
@@ -1,12 +1,12 @@
-use super::ItemId;
-use crate::{grid, player};
+use super::{Flag, ItemId};
+use crate::{asset, grid, player};
 use bevy::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // this stores all the data that the items
 // can modify and is then used throughout the
 // level to enact those behaviours
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Config {
     pub flags: ConfigFlags,
     pub player: player::PlayerBuilder,
@@ -14,19 +14,53 @@ pub struct Config {
 }
 
 impl FromWorld for Config {
-    // returns a default version of the config
     fn from_world(world: &mut World) -> Self {
+        Self::new(
+            world
+                .get_resource::<asset::Materials>()
+                .unwrap(),
+        )
+    }
+}
+
+impl Config {
+    // returns a default version of the config
+    pub fn new(materials: &asset::Materials) -> Self {
         Self {
             flags: ConfigFlags::new(),
-            player: player::PlayerBuilder::from_world(
-                world,
-            ),
-            gun: player::GunBuilder::from_world(world),
+            player: player::PlayerBuilder::new(materials),
+            gun: player::GunBuilder::new(materials),
         }
     }
-}
 
-impl Config {
+    // the numbers that matter to the player named how
+    // they'd think of them, for comparing items
+    pub fn stats(&self) -> Vec<(&'static str, f32)> {
+        let (player, gun) = (&self.player, &self.gun);
+        vec![
+            ("Move speed", player.speed),
+            ("Acceleration", player.acceleration),
+            ("Braking", player.deceleration),
+            ("Size", player.size.x.max(player.size.y)),
+            ("Health", player.health),
+            ("Dash distance", player.dash_distance),
+            ("Dash cooldown", player.dash_cooldown),
+            ("Fire delay", gun.cooldown),
+            ("Inaccuracy", gun.deviation),
+            ("Damage", gun.damage),
+            ("Bullet speed", gun.speed),
+            ("Bullet acceleration", gun.acceleration),
+            ("Bullet drag", gun.drag),
+            ("Range", gun.range()),
+            ("Bullets per shot", gun.projectiles as f32),
+            ("Spread", gun.spread),
+            ("Shots per burst", gun.burst as f32),
+            ("Pierce", gun.pierce as f32),
+            ("Fragments", gun.split as f32),
+            ("Homing", gun.homing),
+            ("Blast radius", gun.explosion),
+        ]
+    }
     // limits all the config values to sensible
     // amounts to avoid weird behaviour
     pub fn clamp(&mut self) {
@@ -54,12 +88,19 @@ impl Config {
         clamp!(
             player.speed =>
             min: 50., max: 400.;
+            player.acceleration =>
+            min: 200.0, max: 10000.0;
+            player.deceleration =>
+            min: 200.0, max: 10000.0;
             player.size =>
             min: Vec2::splat(5.0),
-            max: Vec2::splat(
-                crate::WINDOW_HEIGHT
-                / grid::Grid::HEIGHT as f32
-            );
+            max: Vec2::splat(grid::Grid::TILE_SIZE);
+            player.health =>
+            min: 1.0, max: 10.0;
+            player.dash_distance =>
+            min: 20.0, max: grid::Grid::TILE_SIZE * 5.0;
+            player.dash_cooldown =>
+            min: 0.2, max: 5.0;
         );
         // limiting all the values for the gun
         let gun = &mut self.gun;
@@ -79,50 +120,164 @@ impl Config {
             min: 0.0, max: 2.0;
             gun.lifetime =>
             min: 0.3, max: 100.0;
+            gun.speed =>
+            min: 100.0, max: 1500.0;
+            gun.acceleration =>
+            min: -1000.0, max: 2000.0;
+            gun.drag =>
+            min: 0.0, max: 5.0;
+            gun.damage =>
+            min: 0.1, max: 10.0;
+            gun.pierce =>
+            min: 0, max: 10;
+            gun.split =>
+            min: 0, max: 8;
+            gun.homing =>
+            min: 0.0, max: 10.0;
+            gun.explosion =>
+            min: 0.0, max: grid::Grid::TILE_SIZE * 3.0;
+            gun.projectiles =>
+            min: 1, max: 12;
+            gun.spread =>
+            min: 0.0, max: std::f32::consts::PI;
+            gun.barrel_gap =>
+            min: 0.0, max: self.player.size.x;
+            gun.burst =>
+            min: 1, max: 8;
+            gun.burst_interval =>
+            min: 0.02, max: 0.5;
         );
     }
 }
 
-// this is a wrapper for a hashmap that tells you
-// items are and aren't in the player's inventory
-// and how many of them the player has
-#[derive(Debug)]
-pub struct ConfigFlags(HashMap<ItemId, u32>);
+// a number that items can change, the change is
+// always worked out as a float and rounded back
+// for whole numbers
+trait Field {
+    fn change(&mut self, f: &impl Fn(f32) -> f32);
+}
+
+impl Field for f32 {
+    fn change(&mut self, f: &impl Fn(f32) -> f32) {
+        *self = f(*self);
+    }
+}
+
+impl Field for u32 {
+    fn change(&mut self, f: &impl Fn(f32) -> f32) {
+        // negatives end up as zero
+        *self = f(*self as f32).round().max(0.0) as u32;
+    }
+}
+
+// gives every number items can change a name so that
+// items can be written in the items file
+// each entry takes the form
+// "name" => field, other_field;
+// with every field listed being changed together
+macro_rules! fields {
+    ($($name:literal => $($($field:ident).+),+;)*) => {
+        impl Config {
+            // every name that can be changed
+            pub const FIELDS: &'static [&'static str] =
+                &[$($name),*];
+
+            // changes the named field with f returning
+            // false if there's no field with that name
+            pub fn modify(
+                &mut self,
+                name: &str,
+                f: impl Fn(f32) -> f32,
+            ) -> bool {
+                match name {
+                    $(
+                        $name => {
+                            $(
+                                self.$($field).+.change(&f);
+                            )+
+                        }
+                    )*
+                    _ => return false,
+                }
+                true
+            }
+        }
+    };
+}
+
+fields! {
+    "player.speed" => player.speed;
+    "player.acceleration" => player.acceleration;
+    "player.deceleration" => player.deceleration;
+    "player.size" => player.size.x, player.size.y;
+    "player.health" => player.health;
+    "player.dash_distance" => player.dash_distance;
+    "player.dash_cooldown" => player.dash_cooldown;
+    "gun.size" => gun.size.x, gun.size.y;
+    "gun.size.x" => gun.size.x;
+    "gun.size.y" => gun.size.y;
+    "gun.cooldown" => gun.cooldown;
+    "gun.deviation" => gun.deviation;
+    "gun.lifetime" => gun.lifetime;
+    "gun.speed" => gun.speed;
+    "gun.acceleration" => gun.acceleration;
+    "gun.drag" => gun.drag;
+    "gun.damage" => gun.damage;
+    "gun.pierce" => gun.pierce;
+    "gun.split" => gun.split;
+    "gun.homing" => gun.homing;
+    "gun.explosion" => gun.explosion;
+    "gun.projectiles" => gun.projectiles;
+    "gun.spread" => gun.spread;
+    "gun.barrel_gap" => gun.barrel_gap;
+    "gun.burst" => gun.burst;
+    "gun.burst_interval" => gun.burst_interval;
+}
+
+// this tells you which items are and aren't in the
+// player's inventory and how many of them the player
+// has, as well as which behaviours they turn on
+#[derive(Debug, Clone)]
+pub struct ConfigFlags {
+    items: HashMap<ItemId, u32>,
+    flags: HashSet<Flag>,
+}
 
 impl ConfigFlags {
     // creates a new empty ConfigFlags
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self {
+            items: HashMap::new(),
+            flags: HashSet::new(),
+        }
     }
 
-    // checks if the given item is in the players
-    // inventory
-    pub fn contains(&self, flag: &ItemId) -> bool {
-        self.0.contains_key(flag)
+    // checks if any of the player's items
+    // turn on the given behaviour
+    pub fn contains(&self, flag: Flag) -> bool {
+        self.flags.contains(&flag)
     }
 
     // counts the number of a given item in the player's
     // inventory
-    pub fn count<'a>(&'a self, flag: &ItemId) -> &'a u32 {
-        match self.0.get(flag) {
+    pub fn count<'a>(&'a self, item: &ItemId) -> &'a u32 {
+        match self.items.get(item) {
             Some(count) => count,
             None => &0,
         }
     }
 
     // adds an item to the player's invetory
-    pub fn add(&mut self, flag: ItemId) {
-        match self.0.get_mut(&flag) {
-            // if there is already an entry for
-            // this item, increment the count of
-            // this item
-            Some(count) => *count += 1,
-            // insert a new entry in the hashmap
-            // and give it a value of one
-            None => {
-                self.0.insert(flag, 1);
-            }
-        }
+    pub fn add(&mut self, item: ItemId) {
+        // if there is already an entry for
+        // this item, increment the count of
+        // this item otherwise start it at one
+        *self.items.entry(item).or_insert(0) += 1;
+    }
+
+    // turns on a behaviour
+    pub fn add_flag(&mut self, flag: Flag) {
+        self.flags.insert(flag);
     }
 
     // returns an iterator over all the Items
@@ -130,12 +285,12 @@ impl ConfigFlags {
     pub fn iter(
         &self,
     ) -> impl Iterator<Item = (&ItemId, &u32)> {
-        self.0.iter()
+        self.items.iter()
     }
 }
 
 impl Default for ConfigFlags {
     fn default() -> Self {
-         Self::new()
+        Self::new()
     }
-}
\ No newline at end of file
+}
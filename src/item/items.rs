@@ -1,323 +1,268 @@
-use rand::{prelude::ThreadRng, Rng};
-
 use super::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// unique identifier for each item, the same as the
+// id it's given in the items file
+// is useful as can't send whole items
+// between threads (or save them to a file)
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+#[serde(transparent)]
+pub struct ItemId(String);
+
+impl ItemId {
+    // the raw id for when there's nothing
+    // better to show
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
 
-// unique identifier for each item
-// is useful as can't send Box<dyn Item>
-// between threads (also can't save to file later)
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub enum ItemId {
+// behaviours that can't be described by changing
+// numbers so they're written in code and items
+// turn them on
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize,
+)]
+pub enum Flag {
+    // the gun keeps shooting whilst the trigger is held
     AutoFire,
-    Faster,
-    Smaller,
-    Bigger,
-    Slower,
-    Accuracy,
+    // bullets bounce off walls instead of breaking
     Bouncy,
-    HighCalibre,
-    Laser,
+    // bouncing bullets keep their shape
+    Ricochet,
 }
 
-impl ItemId {
-    // converts an id to its item
-    pub fn to_item(&self) -> Box<dyn Item> {
-        // mimics a match but boxes the expression
-        // that is matched to, making the syntax look less
-        // janky
-        macro_rules! match_boxed {
-            ($($id:pat => $item:expr),*,) => {
-                match self {
-                    $(
-                        $id => Box::new($item),
-                    )*
-                }
-            }
-        }
-        // matching ids into items
-        match_boxed!(
-            ItemId::AutoFire => AutoFire,
-            ItemId::Faster => Faster,
-            ItemId::Smaller => Smaller,
-            ItemId::Bigger => Bigger,
-            ItemId::Slower => Slower,
-            ItemId::Accuracy => Accuracy,
-            ItemId::Bouncy => Bouncy,
-            ItemId::HighCalibre => HighCalibre,
-            ItemId::Laser => Laser,
-        )
-    }
+impl Flag {
+    // every flag in the order their effects are applied
+    pub const ALL: [Flag; 3] =
+        [Flag::AutoFire, Flag::Bouncy, Flag::Ricochet];
 
-    // generates a random item
-    pub fn random(rng: &mut ThreadRng) -> ItemId {
-        match rng.gen_range(0..8) {
-            0 => ItemId::Accuracy,
-            1 => ItemId::AutoFire,
-            2 => ItemId::Bigger,
-            3 => ItemId::Bouncy,
-            4 => ItemId::Faster,
-            5 => ItemId::HighCalibre,
-            6 => ItemId::Laser,
-            7 => ItemId::Slower,
-            8 => ItemId::Smaller,
-            _ => panic!("unreachable"),
+    // changes the config once if the flag is turned on
+    // (knowing every other flag that's on so they
+    // can work together)
+    pub fn apply(
+        &self,
+        config: &mut Config,
+        flags: &ConfigFlags,
+    ) {
+        match self {
+            // bouncing bullets are round unless
+            // they're ricocheting
+            Flag::Bouncy
+                if !flags.contains(Flag::Ricochet) =>
+            {
+                config.gun.size = Vec2::new(3.0, 3.0)
+                    * (config.gun.size.length() / 10.0)
+            }
+            _ => (),
         }
     }
 }
 
-// this is the trait that allows a struct
-// to modify the items config
-pub trait Item {
-    // i have split item modifers up into
-    // three main groups:
-    // adding
-    // subtracting
-    // miscellaneous
-    // adding and multiplying have been split to
-    // prevent the order the items
-    // are stored in from affecting the player's
-    // stats
-    // miscellaneous changes anything that isn't really
-    // a directly important stat i.e. the color of the
-    // player or something
+// the changes to make to named Config fields
+// e.g. {"gun.cooldown": 0.9}
+// (see Config::FIELDS for every name)
+pub type Modifiers = BTreeMap<String, f32>;
+
+// everything about an item as it's written
+// in assets/items.ron
+// adding and multiplying have been split to
+// prevent the order the items
+// are stored in from affecting the player's
+// stats
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemDef {
+    pub id: ItemId,
+    // shown in the item menu
+    pub name: String,
+    pub desc: String,
+    // a couple of letters to stand in for
+    // the item where there isn't room for its name
+    pub icon: String,
+    pub rarity: Rarity,
+    // the hex color of the gun and bullets
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub flags: Vec<Flag>,
+    // added on the first occurence of this item in
+    // the player's inventory (add is used if there
+    // isn't one)
+    #[serde(default)]
+    pub add_first: Option<Modifiers>,
+    // added for every other occurence
+    #[serde(default)]
+    pub add: Modifiers,
+    // same as the adds but multiplied afterwards
+    #[serde(default)]
+    pub mul_first: Option<Modifiers>,
+    #[serde(default)]
+    pub mul: Modifiers,
+}
 
-    // run on the first occurence of this item
-    // in the player's inventory
-    // adding to their stats
-    fn add_first(&self, config: &mut Config) {
-        // default implementation of add first
-        // just falls through to add
-        self.add(config)
+impl ItemDef {
+    // adds this item's modifiers count times
+    pub fn add(&self, config: &mut Config, count: u32) {
+        let first =
+            self.add_first.as_ref().unwrap_or(&self.add);
+        modify(config, first, |value, by| value + by);
+        for _ in 1..count {
+            modify(config, &self.add, |value, by| {
+                value + by
+            });
+        }
     }
-    // run on any item after the first
-    fn add(&self, _config: &mut Config) {}
-    // run on the first occurence of this item
-    // in the player's inventory
-    // multiplying their stats
-    fn mul_first(&self, config: &mut Config) {
-        self.mul(config)
+
+    // multiplies this item's modifiers count times
+    pub fn mul(&self, config: &mut Config, count: u32) {
+        let first =
+            self.mul_first.as_ref().unwrap_or(&self.mul);
+        modify(config, first, |value, by| value * by);
+        for _ in 1..count {
+            modify(config, &self.mul, |value, by| {
+                value * by
+            });
+        }
     }
-    // run on any item after the first
-    fn mul(&self, _config: &mut Config) {}
 
-    // only run once per occurence of an item
+    // only run once per kind of item
     // applies miscelaneous effects like changing
-    // the player's color that only make sense to apply
+    // the gun's color that only make sense to apply
     // once
-    fn misc(
+    pub fn misc(
         &self,
-        _config: &mut Config,
-        _world: &mut World,
-        _flags: &ConfigFlags,
+        config: &mut Config,
+        world: &mut World,
     ) {
-    }
-    // gives the id of the item for saving and loading it
-    fn id(&self) -> ItemId;
-    // gives the name of the item for ingame display
-    fn name(&self) -> String;
-    // gives the description of an item for ingame display
-    fn desc(&self) -> String;
-}
-
-// shorthand for a method that returns the items
-// id or name or desc depending
-// since every item has to have this method so it
-// reduces code bloat
-macro_rules! id {
-    ($id:expr) => {
-        fn id(&self) -> ItemId {
-            $id
+        if let Some(hex) = &self.color {
+            let mut colors = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .unwrap();
+            // the color was checked when it was loaded
+            config.gun.material = colors.add(
+                Color::hex(hex)
+                    .unwrap_or(Color::WHITE)
+                    .into(),
+            );
         }
-    };
-}
+    }
 
-macro_rules! name {
-    ($name:literal) => {
-        fn name(&self) -> String {
-            $name.to_string()
+    // checks everything that can't be checked by
+    // just reading the file
+    pub fn validate(&self) -> Result<(), String> {
+        let modifiers = self
+            .add_first
+            .iter()
+            .chain(self.mul_first.iter())
+            .chain([&self.add, &self.mul].iter().copied());
+        for modifiers in modifiers {
+            validate(modifiers).map_err(|e| {
+                format!("item {} {}", self.id.as_str(), e)
+            })?;
         }
-    };
-}
-
-macro_rules! desc {
-    ($desc:literal) => {
-        fn desc(&self) -> String {
-            $desc.to_string()
+        if let Some(hex) = &self.color {
+            Color::hex(hex).map_err(|_| {
+                format!(
+                    "item {} has invalid color {}",
+                    self.id.as_str(),
+                    hex
+                )
+            })?;
         }
-    };
-}
-
-// makes the players gun automatic
-// so that they can hold down the
-// trigger and it keeps shooting
-// also makes the gun shoot faster
-// and less accurate
-pub struct AutoFire;
-
-impl Item for AutoFire {
-    fn add_first(&self, config: &mut Config) {
-        config.gun.deviation += 0.3;
-    }
-    fn mul(&self, config: &mut Config) {
-        config.gun.cooldown *= 0.9;
-        config.gun.deviation *= 1.2;
+        Ok(())
     }
-    id!(ItemId::AutoFire);
-    name!("Automatic");
-    desc!("Hit the broad side of a barn at 1000RPM");
 }
 
-// makes the player move faster
-// but their bullets live less time
-pub struct Faster;
-
-impl Item for Faster {
-    fn mul_first(&self, config: &mut Config) {
-        config.player.speed *= 1.5;
-        config.gun.lifetime *= 0.8;
-    }
-    fn mul(&self, config: &mut Config) {
-        config.player.speed *= 1.3;
-        config.gun.lifetime *= 0.8;
-    }
-    id!(ItemId::Faster);
-    name!("Turbo the Snail");
-    desc!("Go farther but shorten your bullets lives");
-}
-
-// makes player slower
-// but bullets live longer
-
-pub struct Slower;
-
-impl Item for Slower {
-    fn mul(&self, config: &mut Config) {
-        config.player.speed *= 0.8;
-        config.gun.lifetime *= 1.5;
-    }
-    id!(ItemId::Slower);
-    name!("Lead Boots");
-    desc!("No I only meant the tip");
+// two or more items that do something extra when
+// the player has all of them
+#[derive(Debug, Clone, Deserialize)]
+pub struct Synergy {
+    pub name: String,
+    pub desc: String,
+    // the items that all have to be taken
+    pub requires: Vec<ItemId>,
+    #[serde(default)]
+    pub flags: Vec<Flag>,
+    // applied after every item's modifiers
+    #[serde(default)]
+    pub add: Modifiers,
+    #[serde(default)]
+    pub mul: Modifiers,
 }
 
-// makes the player smaller
-// but slower
-pub struct Smaller;
-
-impl Item for Smaller {
-    fn add_first(&self, config: &mut Config) {
-        config.player.size -= Vec2::splat(5.0);
-    }
-    fn mul(&self, config: &mut Config) {
-        config.player.size *= 0.8;
-        config.player.speed *= 0.8;
+impl Synergy {
+    // whether every item it needs is in flags
+    pub fn is_complete(&self, flags: &ConfigFlags) -> bool {
+        self.requires.iter().all(|id| *flags.count(id) > 0)
     }
-    id!(ItemId::Smaller);
-    // alice in wonderland reference
-    name!("Drink Me");
-    desc!("Shrinks and slows");
-}
-
-// makes the player bigger
-// but also their bullets
-// bigger
-pub struct Bigger;
 
-impl Item for Bigger {
-    fn add_first(&self, config: &mut Config) {
-        config.player.size += Vec2::splat(5.0);
+    pub fn add(&self, config: &mut Config) {
+        modify(config, &self.add, |value, by| value + by);
     }
-    fn mul(&self, config: &mut Config) {
-        config.gun.size *= 1.2;
-        config.player.size *= 1.1;
-    }
-    id!(ItemId::Bigger);
-    name!("Magic Beans");
-    desc!(
-        "Fe Fi Fo Fum! I smell the blood of an Englishman"
-    );
-}
 
-// makes you more accurate
-// but shoot slower
-pub struct Accuracy;
-
-impl Item for Accuracy {
-    fn mul(&self, config: &mut Config) {
-        config.gun.cooldown *= 1.4;
-        config.gun.deviation *= 0.6;
+    pub fn mul(&self, config: &mut Config) {
+        modify(config, &self.mul, |value, by| value * by);
     }
-    id!(ItemId::Accuracy);
-    name!("Thinking Man");
-    desc!("Calculate the likely trajectory of every shot you fire");
-}
 
-// shoots bouncing bullets
-// they are less accurate
-// and live less long
-pub struct Bouncy;
-
-impl Item for Bouncy {
-    fn mul(&self, config: &mut Config) {
-        config.gun.deviation *= 1.6;
-        config.gun.lifetime *= 0.7;
-    }
-    fn misc(
+    // checks it only needs items that exist
+    // and changes fields that exist
+    pub fn validate(
         &self,
-        config: &mut Config,
-        _world: &mut World,
-        _flags: &ConfigFlags,
-    ) {
-        config.gun.size = Vec2::new(3.0, 3.0)
-            * (config.gun.size.length() / 10.0)
+        items: &[ItemDef],
+    ) -> Result<(), String> {
+        if self.requires.len() < 2 {
+            return Err(format!(
+                "synergy {} needs at least two items",
+                self.name
+            ));
+        }
+        if let Some(id) = self.requires.iter().find(|id| {
+            !items.iter().any(|def| def.id == **id)
+        }) {
+            return Err(format!(
+                "synergy {} needs unknown item {}",
+                self.name,
+                id.as_str()
+            ));
+        }
+        for modifiers in [&self.add, &self.mul].iter() {
+            validate(modifiers).map_err(|e| {
+                format!("synergy {} {}", self.name, e)
+            })?;
+        }
+        Ok(())
     }
-    id!(ItemId::Bouncy);
-    name!("Bouncy Bullets");
-    desc!("Boing");
 }
 
-// makes the players bullets wider but also slower
-// and they're more accurate
-struct HighCalibre;
-
-impl Item for HighCalibre {
-    fn mul(&self, config: &mut Config) {
-        config.gun.size.x *= 1.3;
-        config.gun.size.y *= 0.9;
-        config.gun.speed *= 0.8;
-        config.gun.deviation *= 0.7;
+// applies f to every field in modifiers
+fn modify(
+    config: &mut Config,
+    modifiers: &Modifiers,
+    f: impl Fn(f32, f32) -> f32,
+) {
+    for (field, by) in modifiers.iter() {
+        // unknown fields are caught when loading
+        config.modify(field, |value| f(value, *by));
     }
-    id!(ItemId::HighCalibre);
-    name!("Anti Tank Rounds");
-    desc!("Excessive Force");
 }
 
-// makes the player's bullet longer
-// and mover faster but gives it
-// a longer cooldown
-// also makes it red
-struct Laser;
-
-impl Item for Laser {
-    fn mul(&self, config: &mut Config) {
-        config.gun.speed *= 1.4;
-        config.gun.cooldown *= 1.2;
-        config.gun.size.x *= 0.9;
-        config.gun.size.y *= 1.3;
-    }
-    fn misc(
-        &self,
-        config: &mut Config,
-        world: &mut World,
-        _flags: &ConfigFlags,
-    ) {
-        // making the gun and bullets red because laser
-        let mut colors = world
-            .get_resource_mut::<Assets<ColorMaterial>>()
-            .unwrap();
-        config.gun.material =
-            colors.add(Color::rgb_u8(255, 0, 0).into());
+// checks every field in modifiers exists
+fn validate(modifiers: &Modifiers) -> Result<(), String> {
+    match modifiers.keys().find(|field| {
+        !Config::FIELDS.contains(&field.as_str())
+    }) {
+        Some(field) => {
+            Err(format!("changes unknown field {}", field))
+        }
+        None => Ok(()),
     }
-    id!(ItemId::Laser);
-    name!("Laser");
-    desc!("Shark not included");
 }
@@ -0,0 +1,140 @@
+use super::{ItemCatalogue, ItemId};
+use crate::seed;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+// how often an item turns up in the item menu
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Deserialize,
+)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl Rarity {
+    // how likely an item of this rarity is to be
+    // picked compared to the others
+    pub fn weight(&self) -> u32 {
+        match self {
+            Rarity::Common => 6,
+            Rarity::Uncommon => 3,
+            Rarity::Rare => 1,
+        }
+    }
+
+    // the name shown on the item's button
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Uncommon => "Uncommon",
+            Rarity::Rare => "Rare",
+        }
+    }
+}
+
+// every item that can be offered and how likely each
+// one is to be, which is what the item menu draws from
+#[derive(Default)]
+pub struct LootTable {
+    entries: Vec<(ItemId, u32)>,
+}
+
+impl LootTable {
+    // how many items are offered at once
+    pub const OFFER_SIZE: usize = 3;
+
+    // every item in the catalogue weighted by its rarity
+    pub fn new(catalogue: &ItemCatalogue) -> Self {
+        Self {
+            entries: catalogue
+                .defs()
+                .iter()
+                .map(|def| {
+                    (def.id.clone(), def.rarity.weight())
+                })
+                .collect(),
+        }
+    }
+
+    // picks a few different items with rarer items
+    // less likely to turn up
+    pub fn offer(
+        &self,
+        rng: &mut seed::GameRng,
+    ) -> Vec<ItemId> {
+        self.entries
+            .choose_multiple_weighted(
+                rng,
+                Self::OFFER_SIZE,
+                |(_, weight)| *weight as f64,
+            )
+            // the weights come from rarities which are
+            // never zero or negative
+            .expect("item weights must be positive")
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // a handful of items of every rarity
+    fn table() -> LootTable {
+        LootTable::new(&ItemCatalogue::parse(
+            r#"(items: [
+                (id: "A", name: "A", desc: "", icon: "A",
+                    rarity: Common),
+                (id: "B", name: "B", desc: "", icon: "B",
+                    rarity: Common),
+                (id: "C", name: "C", desc: "", icon: "C",
+                    rarity: Uncommon),
+                (id: "D", name: "D", desc: "", icon: "D",
+                    rarity: Uncommon),
+                (id: "E", name: "E", desc: "", icon: "E",
+                    rarity: Rare),
+            ])"#,
+        ))
+    }
+
+    fn rng(seed: u64) -> seed::GameRng {
+        seed::RunSeed::new(seed)
+            .rng(1, seed::RngStream::Items)
+    }
+
+    #[test]
+    fn offers_are_all_different() {
+        let table = table();
+        for seed in 0..100 {
+            let offer = table.offer(&mut rng(seed));
+            assert_eq!(offer.len(), LootTable::OFFER_SIZE);
+            let unique =
+                offer.iter().collect::<HashSet<_>>();
+            assert_eq!(unique.len(), LootTable::OFFER_SIZE);
+        }
+    }
+
+    #[test]
+    fn every_item_turns_up() {
+        let table = table();
+        let offered = (0..100)
+            .flat_map(|seed| table.offer(&mut rng(seed)))
+            .collect::<HashSet<_>>();
+        for (id, _) in table.entries.iter() {
+            assert!(offered.contains(id), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn same_rng_same_offer() {
+        let table = table();
+        assert_eq!(
+            table.offer(&mut rng(1234)),
+            table.offer(&mut rng(1234))
+        );
+    }
+}
@@ -6,15 +6,27 @@ use bevy_rapier2d::prelude::*;
 
 // main() is the entry point for the program
 fn main() {
+    // `--simulate [seed]` plays levels without a window
+    // using a simple bot and prints how each level went
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("--simulate") {
+        simulate(args.next());
+        return;
+    }
+    // loading the player's settings first as the
+    // window and colors are built from them
+    let settings = settings::Settings::load();
     // starts building the app
     let mut app = App::build();
-    app.insert_resource(WindowDescriptor {
-            title: "Neon Daze".to_string(),
-            width: WINDOW_WIDTH,
-            height: WINDOW_HEIGHT,
-            resizable: false,
-            ..Default::default()
-        })
+    // reloading assets (like the items) as soon
+    // as they're changed
+    #[cfg(not(target_arch = "wasm32"))]
+    app.insert_resource(bevy::asset::AssetServerSettings {
+        watch_for_changes: true,
+        ..Default::default()
+    });
+    app.insert_resource(settings.window_descriptor())
+        .insert_resource(settings)
         // these are all the default things the engine enables
         // like sprite rendering, transform managment
         // and much much more
@@ -22,18 +34,24 @@ fn main() {
         // this adds a function that runs when
         // the app first starts
         .add_startup_system(setup.system())
+        // the time the game's logic runs on
+        .add_plugin(clock::ClockPlugin)
         // addding the coodown system
         .add_plugin(cooldown::CooldownPlugin)
         // adding game state controller
         .add_plugin(state::StatePlugin)
+        // adding the run seed for reproducible levels
+        .add_plugin(seed::SeedPlugin)
         // adding the physics system
         .add_plugin(
             RapierPhysicsPlugin::<NoUserData>::default(),
         )
         // disabling gravity (as it comes enabled by default)
-        .add_startup_system(disable_gravity.system())
+        .add_startup_system(phys::disable_gravity.system())
         // adding the asset loader
         .add_plugin(asset::AssetPlugin)
+        // applying changes to the settings
+        .add_plugin(settings::SettingsPlugin)
         // adding the menus
         .add_plugin(menus::MenuPlugin)
         // this adds the build function of the PlayerPlugin
@@ -41,8 +59,23 @@ fn main() {
         // adds grid to game
         .add_plugin(grid::GridPlugin)
         .add_plugin(player::PlayerPlugin)
+        // turning buttons into actions
+        .add_plugin(actions::ActionPlugin)
+        // reading the keyboard and mouse into the player's input
+        .add_plugin(player::input::DeviceInputPlugin)
+        // making the camera follow the player
+        .add_plugin(camera::CameraPlugin)
         .add_plugin(enemies::EnemyPlugin)
+        // adding health and damage for everything
+        // that can get hurt
+        .add_plugin(health::HealthPlugin)
         .add_plugin(item::ItemPlugin)
+        // saving the run between levels
+        .add_plugin(save::SavePlugin)
+        // keeping score
+        .add_plugin(stats::StatsPlugin)
+        // showing how the level is going
+        .add_plugin(hud::HudPlugin)
         .add_plugin(just_spawned::JustSpawnedPlugin);
     #[cfg(target_arch = "wasm32")]
     app.add_plugin(bevy_webgl2::WebGL2Plugin);
@@ -50,12 +83,29 @@ fn main() {
     app.run();
 }
 
-// disables gravity by setting
-// it to zero
-fn disable_gravity(
-    mut rapier_cofig: ResMut<RapierConfiguration>,
-) {
-    rapier_cofig.gravity = Vec2::ZERO.into();
+// runs the headless simulation and prints its results
+fn simulate(seed: Option<String>) {
+    let run_seed = match seed {
+        Some(seed) => seed::RunSeed::new(
+            seed.parse().expect("seed must be a number"),
+        ),
+        None => seed::RunSeed::default(),
+    };
+    println!("seed: {}", run_seed.value());
+    let report = sim::run(
+        sim::headless_app(
+            run_seed,
+            sim::InputScript::nearest_enemy(),
+        ),
+        // two minutes of game time
+        120 * sim::FRAMES_PER_SEC,
+    );
+    for outcome in report.outcomes.iter() {
+        println!(
+            "level {}: {:?} after {:.1}s",
+            outcome.level, outcome.result, outcome.secs
+        );
+    }
 }
 
 // this function gets run during startup and its parameters
@@ -65,7 +115,10 @@ fn setup(mut commands: Commands) {
     // renders of sprites and such to the screen
     commands
         .spawn_bundle(OrthographicCameraBundle::new_2d())
-        .insert(MainCamera);
+        .insert(MainCamera)
+        // following the player so levels can be
+        // bigger than the window
+        .insert(camera::CameraFollow::default());
     // renders of UI elements over the game
     commands.spawn_bundle(UiCameraBundle::default());
 }
@@ -97,6 +150,8 @@ const WINDOW_HEIGHT: f32 = 800.0;
 
 pub mod phys;
 
+pub mod clock;
+
 pub mod cooldown;
 
 pub mod item;
@@ -104,3 +159,23 @@ pub mod item;
 pub mod enemies;
 
 pub mod just_spawned;
+
+pub mod seed;
+
+pub mod sim;
+
+pub mod camera;
+
+pub mod health;
+
+pub mod save;
+
+pub mod storage;
+
+pub mod stats;
+
+pub mod actions;
+
+pub mod settings;
+
+pub mod hud;
@@ -2,8 +2,10 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 // importing state module
-use crate::{asset, grid, item, player, state};
-use rand::rngs::ThreadRng;
+use crate::{
+    actions, asset, grid, item, player, save, seed, state,
+    stats,
+};
 
 // same as PlayerPlugin
 pub struct MenuPlugin;
@@ -15,157 +17,250 @@ impl Plugin for MenuPlugin {
         PauseMenu::build(app);
         Countdown::build(app);
         ItemMenu::build(app);
+        GameOverMenu::build(app);
+        // letting every menu be used with a gamepad
+        focus::MenuFocus::build(app);
+        widget::Widget::build(app);
+        controls::ControlsMenu::build(app);
+        settings::SettingsMenu::build(app);
     }
 }
 
-// holds data about any UI elements
-// on the main menu
-#[derive(Clone, Debug)]
-enum MainMenu {
-    // this represents a simple button
-    // with text on it that is a string
-    Button { text: String },
+// the root of the main menu's ui
+struct MainMenu;
+
+// what each button on the main menu does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MainMenuButton {
+    Play,
+    Continue,
+    Settings,
+    SeededRun,
+    // there's nothing to quit to in a browser
+    #[cfg(not(target_arch = "wasm32"))]
+    Quit,
+}
+
+// the seed being typed in for a seeded run
+// None until the seeded run button is clicked
+#[derive(Debug, Clone, Default)]
+struct SeedEntry(Option<String>);
+
+impl SeedEntry {
+    // the most digits a u64 can have
+    const MAX_DIGITS: usize = 20;
+
+    // the seed typed in so far if it's a valid one
+    fn seed(&self) -> Option<seed::RunSeed> {
+        self.0
+            .as_ref()?
+            .parse()
+            .ok()
+            .map(seed::RunSeed::new)
+    }
+}
+
+impl MainMenuButton {
+    // the text shown on the button
+    fn label(&self, entry: &SeedEntry) -> String {
+        match self {
+            MainMenuButton::Play => "Play".to_string(),
+            MainMenuButton::Continue => {
+                "Continue".to_string()
+            }
+            MainMenuButton::Settings => {
+                "Settings".to_string()
+            }
+            MainMenuButton::SeededRun => match &entry.0 {
+                None => "Seeded Run".to_string(),
+                // showing a cursor after what's been typed
+                Some(digits) => {
+                    format!("Seed: {}_", digits)
+                }
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            MainMenuButton::Quit => "Quit".to_string(),
+        }
+    }
 }
 
 impl MainMenu {
     // adds the systems that control the main menu
     fn build(app: &mut AppBuilder) {
-        app.add_system_set(
-            // runs when the main menu state is first entered
-            SystemSet::on_enter(state::GameState::MainMenu)
+        app.init_resource::<SeedEntry>()
+            .add_system_set(
+                // runs when the main menu state is first entered
+                SystemSet::on_enter(
+                    state::GameState::MainMenu,
+                )
                 .with_system(MainMenu::setup.system()),
-        )
-        .add_system_set(
-            // runs every frame in the main menu
-            SystemSet::on_update(
-                state::GameState::MainMenu,
             )
-            .with_system(MainMenu::update.system()),
-        )
-        .add_system(state::GameState::despawn::<
-            MainMenu,
-        >(
-            state::GameState::MainMenu
-        ));
+            .add_system_set(
+                // runs every frame in the main menu
+                SystemSet::on_update(
+                    state::GameState::MainMenu,
+                )
+                .with_system(MainMenu::update.system())
+                .with_system(MainMenu::type_seed.system()),
+            )
+            .add_system(state::GameState::despawn::<
+                MainMenu,
+            >(
+                state::GameState::MainMenu
+            ));
     }
 
     // sets up the ui
     fn setup(
         mut commands: Commands,
-        // controls the loading of assets from a filesystem
-        // like fonts and images
-        asset_server: Res<AssetServer>,
         materials: Res<asset::Materials>,
+        mut entry: ResMut<SeedEntry>,
     ) {
-        // creating a new button with the text Play
-        let play_button = MainMenu::Button {
-            text: "Play".to_string(),
-        };
-        // displaying that button on screen
-        play_button.spawn(
+        // forgetting any seed from last time
+        *entry = SeedEntry::default();
+        let mut buttons = vec![MainMenuButton::Play];
+        // only offering to continue if
+        // there's a saved run to continue
+        if save::RunSave::load().is_some() {
+            buttons.push(MainMenuButton::Continue);
+        }
+        buttons.push(MainMenuButton::SeededRun);
+        buttons.push(MainMenuButton::Settings);
+        #[cfg(not(target_arch = "wasm32"))]
+        buttons.push(MainMenuButton::Quit);
+        spawn_button_list(
             &mut commands,
-            &asset_server,
-            &*materials,
+            &materials,
+            MainMenu,
+            buttons
+                .into_iter()
+                .map(|button| {
+                    (button, button.label(&entry))
+                })
+                .collect(),
         );
     }
-    fn spawn(
-        self,
-        commands: &mut Commands,
-        asset_server: &AssetServer,
-        materials: &asset::Materials,
-    ) {
-        match self {
-            MainMenu::Button { ref text } => {
-                // create a new empty entity
-                let mut entity = commands.spawn();
-                // make it into a button
-                entity.insert_bundle(ButtonBundle {
-                    style: Style {
-                        size: Size::new(
-                            Val::Percent(30.0),
-                            Val::Percent(10.0),
-                        ),
-                        // center button
-                        margin: Rect::all(Val::Auto),
-                        // horizontally center child text
-                        justify_content:
-                            JustifyContent::Center,
-                        // vertically center child text
-                        align_items: AlignItems::Center,
-                        ..Default::default()
-                    },
-                    // make the button black
-                    material: materials
-                        .button_normal
-                        .clone(),
-                    ..Default::default()
-                });
-                // add on buttons data for processing when clicked
-                entity.insert(self.clone());
-                // add a child of the button which displays
-                // text that's aligned with that button
-                entity.with_children(|parent| {
-                    parent.spawn_bundle(TextBundle {
-                        // adds some text
-                        text: Text::with_section(
-                            // our actual text from MainMenu::Button
-                            text,
-                            TextStyle {
-                                // setting the font
-                                font: asset_server.load(
-                                    "fonts/SkyhookMono.ttf",
-                                ),
-                                // setting its size
-                                font_size: 40.0,
-                                // setting its color
-                                color: Color::rgb(
-                                    0.9, 0.9, 0.9,
-                                ),
-                            },
-                            Default::default(),
-                        ),
-                        ..Default::default()
-                    });
-                });
-            }
-        }
-    }
+
     // runs every frame to handle ui interactions
     fn update(
+        mut commands: Commands,
         mut game_state: ResMut<State<state::GameState>>,
+        mut entry: ResMut<SeedEntry>,
+        #[cfg(not(target_arch = "wasm32"))]
+        mut exit: EventWriter<bevy::app::AppExit>,
         query: Query<
-            (&Interaction, &MainMenu),
+            (&Interaction, &MainMenuButton),
             (Changed<Interaction>, With<Button>),
         >,
     ) {
-        // whilst there is only one button
-        // for now there will be more
-        // and all of them must work
-        for (interaction, elem) in query.iter() {
-            // pattern matching to filter out any events that aren't a button
-            // with text being clicked
-            if let (
-                Interaction::Clicked,
-                MainMenu::Button { text },
-            ) = (interaction, elem)
-            {
-                // whilst matching against raw strings
-                // is pretty error prone, it shortens
-                // code significantly and means that
-                // a button has to do what the text on it says
-                match text.as_str() {
-                    // if the clicked button was the play button
-                    "Play" => {
-                        // sets the GameState to InLevel, removing MainMenu
+        for (interaction, button) in query.iter() {
+            if *interaction != Interaction::Clicked {
+                continue;
+            }
+            match button {
+                // sets the GameState to LoadingLevel,
+                // removing MainMenu
+                // every run starts with a fresh random seed
+                // (not picked on game over so the summary
+                // still has the last run's seed)
+                MainMenuButton::Play => {
+                    commands.insert_resource(
+                        seed::RunSeed::default(),
+                    );
+                    game_state
+                        .set(state::GameState::LoadingLevel)
+                        .unwrap();
+                }
+                // loads the saved run and goes back to
+                // picking an item for the next level
+                MainMenuButton::Continue => {
+                    if let Some(save) =
+                        save::RunSave::load()
+                    {
+                        save.restore(&mut commands);
                         game_state
-                            .set(state::GameState::LoadingLevel)
+                            .set(state::GameState::ItemMenu)
                             .unwrap();
                     }
-                    // just crash if a button that has invalid text is clicked
-                    other => panic!(
-                        "Unrecognised button: {}",
-                        other
-                    ),
+                }
+                // the first click starts typing in a seed
+                // and the second plays it
+                MainMenuButton::SeededRun => {
+                    match entry.seed() {
+                        Some(seed) => {
+                            commands.insert_resource(seed);
+                            game_state
+                                .set(
+                                    state::GameState::LoadingLevel,
+                                )
+                                .unwrap();
+                        }
+                        None if entry.0.is_none() => {
+                            entry.0 = Some(String::new());
+                        }
+                        // nothing typed in yet
+                        None => (),
+                    }
+                }
+                // opens the settings screen
+                MainMenuButton::Settings => {
+                    game_state
+                        .set(state::GameState::Settings)
+                        .unwrap();
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                MainMenuButton::Quit => {
+                    exit.send(bevy::app::AppExit)
+                }
+            }
+        }
+    }
+
+    // types digits into the seed once the seeded
+    // run button has been clicked
+    fn type_seed(
+        mut chars: EventReader<ReceivedCharacter>,
+        keys: Res<Input<KeyCode>>,
+        mut entry: ResMut<SeedEntry>,
+        buttons: Query<(&MainMenuButton, &Children)>,
+        mut texts: Query<&mut Text>,
+    ) {
+        if entry.0.is_none() {
+            // still reading the events so typing
+            // before clicking isn't picked up later
+            chars.iter().for_each(drop);
+            return;
+        }
+        let typed = chars
+            .iter()
+            .map(|event| event.char)
+            .filter(char::is_ascii_digit)
+            .collect::<String>();
+        let erase = keys.just_pressed(KeyCode::Back);
+        // only touching the entry when something was
+        // typed so it isn't marked as changed every frame
+        if !typed.is_empty() || erase {
+            let digits = entry.0.as_mut().unwrap();
+            if erase {
+                digits.pop();
+            }
+            digits.push_str(&typed);
+            digits.truncate(SeedEntry::MAX_DIGITS);
+        }
+        // updating the button's text in place rather than
+        // respawning the menu so the focus isn't lost
+        if !entry.is_changed() {
+            return;
+        }
+        for (button, children) in buttons.iter() {
+            if *button != MainMenuButton::SeededRun {
+                continue;
+            }
+            for child in children.iter() {
+                if let Ok(mut text) = texts.get_mut(*child)
+                {
+                    text.sections[0].value =
+                        button.label(&entry);
                 }
             }
         }
@@ -174,26 +269,130 @@ impl MainMenu {
 
 struct PauseMenu;
 
+// what each button on the pause menu does
+#[derive(Debug, Clone, Copy)]
+enum PauseButton {
+    Resume,
+    RestartLevel,
+    AbandonRun,
+    Settings,
+}
+
+// the event to send once the pause menu has closed
+// as the level can only be restarted or abandoned
+// from the InLevel state, not from on top of it
+#[derive(Default)]
+struct PauseChoice(Option<state::GameEvent>);
+
 impl PauseMenu {
     pub fn build(app: &mut AppBuilder) {
-        app.add_system(PauseMenu::enter_or_exit.system())
+        app.init_resource::<PauseChoice>()
+            .add_system(PauseMenu::enter_or_exit.system())
             .add_system_set(
                 SystemSet::on_enter(
                     state::GameState::Pause,
                 )
                 .with_system(PauseMenu::spawn.system()),
             )
+            // coming back from the settings
+            .add_system_set(
+                SystemSet::on_resume(
+                    state::GameState::Pause,
+                )
+                .with_system(PauseMenu::spawn.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(
+                    state::GameState::Pause,
+                )
+                .with_system(
+                    PauseMenu::interactions.system(),
+                ),
+            )
+            .add_system_set(
+                SystemSet::on_resume(
+                    state::GameState::InLevel,
+                )
+                .with_system(
+                    PauseMenu::follow_through.system(),
+                ),
+            )
             .add_system(state::GameState::despawn::<
                 PauseMenu,
             >(
                 state::GameState::Pause
-            ));
+            ))
+            // hiding it whilst the settings are open
+            .add_system(
+                state::GameState::despawn_on_pause::<
+                    PauseMenu,
+                >(state::GameState::Pause),
+            );
+    }
+
+    // handles the pause menu's buttons being clicked
+    fn interactions(
+        mut app_state: ResMut<State<state::GameState>>,
+        mut choice: ResMut<PauseChoice>,
+        mut rapier_cofig: ResMut<RapierConfiguration>,
+        lives: Res<player::Lives>,
+        query: Query<
+            (&Interaction, &PauseButton),
+            (Changed<Interaction>, With<Button>),
+        >,
+    ) {
+        for (interaction, button) in query.iter() {
+            if *interaction != Interaction::Clicked {
+                continue;
+            }
+            // what to do once back in the level
+            choice.0 = match button {
+                PauseButton::Resume => None,
+                // restarting on the last life would end
+                // the run so it isn't allowed
+                PauseButton::RestartLevel
+                    if lives.0 <= 1 =>
+                {
+                    continue;
+                }
+                // getting hit is what already restarts the
+                // level and takes away a life
+                PauseButton::RestartLevel => {
+                    Some(state::GameEvent::PlayerHit)
+                }
+                PauseButton::AbandonRun => {
+                    Some(state::GameEvent::GameOver)
+                }
+                // opening the settings on top of the pause
+                // menu so back comes back here
+                PauseButton::Settings => {
+                    app_state
+                        .push(state::GameState::Settings)
+                        .unwrap();
+                    continue;
+                }
+            };
+            // every other button leaves the pause menu
+            rapier_cofig.physics_pipeline_active = true;
+            app_state.pop().unwrap();
+        }
+    }
+
+    // sends the event picked in the pause
+    // menu now it's back in the level
+    fn follow_through(
+        mut choice: ResMut<PauseChoice>,
+        mut game_events: EventWriter<state::GameEvent>,
+    ) {
+        if let Some(event) = choice.0.take() {
+            game_events.send(event);
+        }
     }
 
     // handles entering/exiting the pause menu
     fn enter_or_exit(
         mut app_state: ResMut<State<state::GameState>>,
-        mut keys: ResMut<Input<KeyCode>>,
+        mut actions: ResMut<actions::Actions>,
         mut is_pause_held: Local<bool>,
         mut rapier_cofig: ResMut<RapierConfiguration>,
     ) {
@@ -201,14 +400,11 @@ impl PauseMenu {
         // is_pause_held so that holding down
         // the keys doesn't open and close the
         // menu over and over again
-        if keys.just_released(KeyCode::Escape)
-            || keys.just_released(KeyCode::P)
-        {
+        if actions.just_released(actions::Action::Pause) {
             *is_pause_held = false;
         }
         // checks if the keys have been just pressed
-        if (keys.just_pressed(KeyCode::Escape)
-            || keys.just_pressed(KeyCode::P))
+        if actions.just_pressed(actions::Action::Pause)
             && !*is_pause_held
         {
             // matching against the current app state
@@ -236,27 +432,32 @@ impl PauseMenu {
                 );
             }
             // reseting keys for engine jank reasons
-            keys.reset(KeyCode::Escape);
-            keys.reset(KeyCode::P);
+            actions.consume(actions::Action::Pause);
             *is_pause_held = true;
         }
     }
 
-    // spawns in the pause menu indicator
+    // spawns in the pause menu with how the
+    // run is going, the items and the buttons
+    #[allow(clippy::too_many_arguments)]
     fn spawn(
         mut commands: Commands,
         materials: Res<asset::Materials>,
         difficulty: Res<grid::Difficulty>,
         items: Res<item::ItemManager>,
+        config: Res<item::Config>,
+        catalogue: Res<item::ItemCatalogue>,
         lives: Res<player::Lives>,
+        run_seed: Res<seed::RunSeed>,
     ) {
+        let style = |font_size| TextStyle {
+            font: materials.main_font.clone(),
+            font_size,
+            color: Color::rgb(0.9, 0.9, 0.9),
+        };
         let mut text = Text::with_section(
             "Paused\n",
-            TextStyle {
-                font: materials.main_font.clone(),
-                font_size: 40.0,
-                color: Color::rgb(0.9, 0.9, 0.9),
-            },
+            style(40.0),
             TextAlignment {
                 vertical: VerticalAlign::Center,
                 horizontal: HorizontalAlign::Center,
@@ -264,37 +465,254 @@ impl PauseMenu {
         );
         text.sections.push(TextSection {
             value: format!(
-                "Level {}\n",
-                difficulty.level()
+                "Level {}  Lives: {}\n",
+                difficulty.level(),
+                lives.0
             ),
-            style: TextStyle {
-                font: materials.main_font.clone(),
-                font_size: 20.0,
-                color: Color::rgb(0.9, 0.9, 0.9),
-            },
+            style: style(20.0),
         });
+        // showing the seed so the run can be shared
         text.sections.push(TextSection {
-            value: format!("Lives: {}\n", lives.0),
-            style: TextStyle {
-                font: materials.main_font.clone(),
-                font_size: 20.0,
-                color: Color::rgb(0.9, 0.9, 0.9),
-            },
+            value: format!("Seed: {}\n", run_seed.value()),
+            style: style(20.0),
         });
-        text.sections.push(TextSection {
-            value: format!("Items: {}\n", items.list()),
-            style: TextStyle {
-                font: materials.main_font.clone(),
-                font_size: 20.0,
-                color: Color::rgb(0.9, 0.9, 0.9),
+        // each kind of item once in the order they
+        // were picked up, with how many there are
+        let mut inventory = Text::with_section(
+            "Items\n",
+            style(20.0),
+            Default::default(),
+        );
+        let mut listed = Vec::new();
+        for id in items.ids() {
+            if listed.contains(id) {
+                continue;
+            }
+            listed.push(id.clone());
+            let count = config.flags.count(id);
+            inventory.sections.push(TextSection {
+                value: if *count > 1 {
+                    format!(
+                        "{} x{}\n",
+                        catalogue.name(id),
+                        count
+                    )
+                } else {
+                    format!("{}\n", catalogue.name(id))
+                },
+                style: style(16.0),
+            });
+        }
+        if listed.is_empty() {
+            inventory.sections.push(TextSection {
+                value: "None yet\n".to_string(),
+                style: style(16.0),
+            });
+        }
+        let restart = if lives.0 > 1 {
+            "Restart Level (-1 life)"
+        } else {
+            "Restart Level (needs 2 lives)"
+        };
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(
+                        Val::Percent(100.0),
+                        Val::Percent(100.0),
+                    ),
+                    // top to bottom
+                    flex_direction:
+                        FlexDirection::ColumnReverse,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: materials.menu_overlay.clone(),
+                ..Default::default()
+            })
+            .insert(PauseMenu)
+            .with_children(|parent| {
+                for text in vec![text, inventory] {
+                    parent.spawn_bundle(TextBundle {
+                        text,
+                        style: Style {
+                            margin: Rect::all(Val::Px(
+                                10.0,
+                            )),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    });
+                }
+                spawn_buttons(
+                    parent,
+                    &materials,
+                    vec![
+                        (
+                            PauseButton::Resume,
+                            "Resume".to_string(),
+                        ),
+                        (
+                            PauseButton::RestartLevel,
+                            restart.to_string(),
+                        ),
+                        (
+                            PauseButton::AbandonRun,
+                            "Abandon Run".to_string(),
+                        ),
+                        (
+                            PauseButton::Settings,
+                            "Settings".to_string(),
+                        ),
+                    ],
+                );
+            });
+    }
+}
+
+// shows how the run went once the game is over
+struct GameOverMenu;
+
+impl GameOverMenu {
+    // how many of the best runs are shown
+    const SHOWN: usize = 5;
+
+    pub fn build(app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_enter(state::GameState::GameOver)
+                .with_system(
+                    GameOverMenu::spawn
+                        .system()
+                        .after(stats::StatsSystem::Submit),
+                ),
+        )
+        .add_system_set(
+            SystemSet::on_update(
+                state::GameState::GameOver,
+            )
+            .with_system(GameOverMenu::dismiss.system()),
+        )
+        .add_system(state::GameState::despawn::<
+            GameOverMenu,
+        >(
+            state::GameState::GameOver
+        ));
+    }
+
+    // spawns in the summary of the run
+    // and the leaderboard
+    fn spawn(
+        mut commands: Commands,
+        materials: Res<asset::Materials>,
+        run_stats: Res<stats::RunStats>,
+        leaderboard: Res<stats::Leaderboard>,
+        catalogue: Res<item::ItemCatalogue>,
+    ) {
+        let style = |font_size| TextStyle {
+            font: materials.main_font.clone(),
+            font_size,
+            color: Color::rgb(0.9, 0.9, 0.9),
+        };
+        let mut text = Text::with_section(
+            "Game Over\n",
+            style(40.0),
+            TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
             },
+        );
+        // adds a line of normal sized text
+        let mut line = |value: String| {
+            text.sections.push(TextSection {
+                value: value + "\n",
+                style: style(20.0),
+            })
+        };
+        line(format!(
+            "Levels cleared: {}",
+            run_stats.levels_cleared()
+        ));
+        for (i, time) in
+            run_stats.level_times.iter().enumerate()
+        {
+            line(format!(
+                "  Level {}: {:.1}s",
+                i + 1,
+                time
+            ));
+        }
+        line(format!(
+            "  Died on level {} after {:.1}s",
+            run_stats.levels_cleared() + 1,
+            run_stats.current_time
+        ));
+        line(format!("Kills: {}", run_stats.kills));
+        line(format!("Shots fired: {}", run_stats.shots));
+        line(format!(
+            "Accuracy: {:.0}%",
+            run_stats.accuracy()
+        ));
+        line(format!(
+            "Items: {}",
+            run_stats
+                .items
+                .iter()
+                .map(|id| catalogue.name(id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        line(String::new());
+        line(match leaderboard.latest {
+            Some(rank) => {
+                format!("New best run! Rank #{}", rank + 1)
+            }
+            None => "Best runs".to_string(),
         });
+        for (i, record) in leaderboard
+            .entries
+            .iter()
+            .take(GameOverMenu::SHOWN)
+            .enumerate()
+        {
+            line(format!(
+                "#{} {} levels {} kills {:.0}% {:.0}s (seed {})",
+                i + 1,
+                record.levels_cleared,
+                record.kills,
+                record.accuracy,
+                record.time,
+                record.seed
+            ));
+        }
+        line(String::new());
+        line("Click to continue".to_string());
         commands
             .spawn_bundle(TextBundle {
                 text,
                 ..Default::default()
             })
-            .insert(PauseMenu);
+            .insert(GameOverMenu);
+    }
+
+    // goes back to the main menu on any click or button
+    fn dismiss(
+        keys: Res<Input<KeyCode>>,
+        mouse: Res<Input<MouseButton>>,
+        gamepad_buttons: Res<Input<GamepadButton>>,
+        mut game_state: ResMut<State<state::GameState>>,
+    ) {
+        if keys.get_just_pressed().next().is_some()
+            || mouse.just_pressed(MouseButton::Left)
+            || gamepad_buttons
+                .get_just_pressed()
+                .next()
+                .is_some()
+        {
+            game_state
+                .overwrite_set(state::GameState::MainMenu)
+                .unwrap();
+        }
     }
 }
 
@@ -332,13 +750,6 @@ impl Countdown {
     }
 }
 
-// for now the default countodown is from 3
-impl Default for Countdown {
-    fn default() -> Self {
-        Self::new(3.0)
-    }
-}
-
 impl Countdown {
     // adding the cooldowns systems to the game
     pub fn build(app: &mut AppBuilder) {
@@ -370,9 +781,13 @@ impl Countdown {
     fn spawn(
         mut commands: Commands,
         materials: Res<asset::Materials>,
+        settings: Res<crate::settings::Settings>,
         mut rapier_cofig: ResMut<RapierConfiguration>,
         mut game_state: ResMut<State<state::GameState>>,
     ) {
+        // how long the countdown is
+        // is up to the player
+        let countdown = Countdown::new(settings.countdown);
         // putting another state over the current
         // GameState, just like Pause does
         // to pause the game logic being run
@@ -386,7 +801,7 @@ impl Countdown {
         commands
             .spawn_bundle(TextBundle {
                 text: Text::with_section(
-                    Countdown::default().start.to_string(),
+                    countdown.start.to_string(),
                     TextStyle {
                         font: materials.main_font.clone(),
                         font_size: 120.0,
@@ -399,7 +814,7 @@ impl Countdown {
                 ),
                 ..Default::default()
             })
-            .insert(Countdown::default());
+            .insert(countdown);
     }
 
     // advances the countdown timer
@@ -468,46 +883,144 @@ pub struct ItemMenuButton {
     item: item::ItemId,
 }
 
+// the other choices on the item menu
+// which are paid for with lives
+#[derive(Debug, Clone, Copy)]
+enum ItemMenuOption {
+    // swaps the offer for a new one for a life
+    Reroll,
+    // takes no item but gets a life back
+    Skip,
+}
+
+// the items on offer and how many
+// times they've been rerolled
+#[derive(Default)]
+pub struct ItemOffer {
+    items: Vec<item::ItemId>,
+    rerolls: u32,
+}
+
+impl ItemOffer {
+    // an offer still to be rolled that picks up from
+    // where a saved run left off
+    pub fn rerolled(rerolls: u32) -> Self {
+        Self {
+            items: Vec::new(),
+            rerolls,
+        }
+    }
+
+    // how many times the offer has been rerolled
+    pub fn rerolls(&self) -> u32 {
+        self.rerolls
+    }
+
+    // forgets the offer once the menu is left so the
+    // next one starts without any rerolls
+    fn clear(mut offer: ResMut<ItemOffer>) {
+        *offer = ItemOffer::default();
+    }
+
+    // the offer after rerolling it a number of times
+    // every offer comes from the level's rng so the same
+    // seed always offers the same items
+    fn new(
+        table: &item::LootTable,
+        run_seed: &seed::RunSeed,
+        level: u32,
+        rerolls: u32,
+    ) -> Self {
+        let mut rng =
+            run_seed.rng(level, seed::RngStream::Items);
+        let mut items = table.offer(&mut rng);
+        for _ in 0..rerolls {
+            items = table.offer(&mut rng);
+        }
+        Self { items, rerolls }
+    }
+}
+
 impl ItemMenu {
     // adding item menu's logic to
     // the app
     pub fn build(app: &mut AppBuilder) {
-        app.add_system_set(
-            SystemSet::on_enter(state::GameState::ItemMenu)
-                .with_system(ItemMenu::spawn.system()),
-        )
-        .add_system_set(
-            SystemSet::on_update(
-                state::GameState::ItemMenu,
+        app.init_resource::<ItemOffer>()
+            .add_system_set(
+                SystemSet::on_enter(
+                    state::GameState::ItemMenu,
+                )
+                .with_system(ItemMenu::roll.system()),
             )
-            .with_system(ItemMenu::interactions.system()),
-        )
-        .add_system(state::GameState::despawn::<
-            ItemMenu,
-        >(
-            state::GameState::ItemMenu
-        ));
+            .add_system_set(
+                SystemSet::on_update(
+                    state::GameState::ItemMenu,
+                )
+                .with_system(ItemMenu::refresh.system())
+                .with_system(
+                    ItemMenu::interactions.system(),
+                ),
+            )
+            .add_system_set(
+                SystemSet::on_exit(
+                    state::GameState::ItemMenu,
+                )
+                .with_system(ItemOffer::clear.system()),
+            )
+            .add_system(state::GameState::despawn::<
+                ItemMenu,
+            >(
+                state::GameState::ItemMenu
+            ));
     }
 
-    // spawn in the item menu
-    // for the player to see
-    pub fn spawn(
+    // picks the items offered for this level
+    // (a continued run keeps the rerolls it had)
+    fn roll(
+        mut offer: ResMut<ItemOffer>,
+        table: Res<item::LootTable>,
+        difficulty: Res<grid::Difficulty>,
+        run_seed: Res<seed::RunSeed>,
+    ) {
+        *offer = ItemOffer::new(
+            &table,
+            &run_seed,
+            difficulty.level(),
+            offer.rerolls,
+        );
+    }
+
+    // (re)spawns the item menu for the player to see
+    // whenever the offer, the lives paying for it
+    // or the items file change
+    fn refresh(
         mut commands: Commands,
         materials: Res<asset::Materials>,
+        offer: Res<ItemOffer>,
+        lives: Res<player::Lives>,
+        catalogue: Res<item::ItemCatalogue>,
+        items: Res<item::ItemManager>,
+        menu: Query<Entity, With<ItemMenu>>,
     ) {
-        // getting an rng generator
-        let mut rng = ThreadRng::default();
-        // creates a random item
-        macro_rules! rand_item {
-            () => {
-                item::ItemId::random(&mut rng).to_item()
-            };
+        if menu.iter().next().is_some()
+            && !offer.is_changed()
+            && !lives.is_changed()
+            && !catalogue.is_changed()
+        {
+            return;
+        }
+        for entity in menu.iter() {
+            commands.entity(entity).despawn_recursive();
         }
-        // getting three random items
-        let items =
-            vec![rand_item!(), rand_item!(), rand_item!()];
+        // what the player's stats are now to compare
+        // each item against
+        let before = item::ItemManager::configure(
+            item::Config::new(&materials),
+            items.ids(),
+            &catalogue,
+        );
         // spawning the div that contains the
-        // three item buttons
+        // item buttons
         commands
             .spawn_bundle(NodeBundle {
                 style: Style {
@@ -526,15 +1039,112 @@ impl ItemMenu {
             })
             .insert(ItemMenu)
             .with_children(|parent| {
-                // spawning the three buttons
-                for item in items.into_iter() {
+                // spawning a button for each item
+                for def in offer
+                    .items
+                    .iter()
+                    .filter_map(|id| catalogue.get(id))
+                {
+                    // shows off any synergy the item
+                    // would finish
+                    let synergies = catalogue
+                        .completed_by(&def.id, items.ids())
+                        .collect::<Vec<_>>();
+                    // and what it'd actually do to
+                    // the player's stats
+                    let mut ids = items.ids().to_vec();
+                    ids.push(def.id.clone());
+                    let after =
+                        item::ItemManager::configure(
+                            item::Config::new(&materials),
+                            &ids,
+                            &catalogue,
+                        );
                     ItemMenu::spawn_button(
                         parent,
-                        item,
+                        def,
+                        &synergies,
+                        &ItemMenu::changes(&before, &after),
                         &*materials,
                     )
                 }
             });
+        // the reroll and skip buttons along the bottom
+        let reroll = if lives.0 > 1 {
+            "Reroll (-1 life)"
+        } else {
+            "Reroll (needs 2 lives)"
+        };
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(
+                        Val::Percent(100.0),
+                        Val::Auto,
+                    ),
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(0.0),
+                        bottom: Val::Px(10.0),
+                        ..Default::default()
+                    },
+                    flex_direction:
+                        FlexDirection::ColumnReverse,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: materials.menu_overlay.clone(),
+                ..Default::default()
+            })
+            .insert(ItemMenu)
+            .with_children(|parent| {
+                spawn_buttons(
+                    parent,
+                    &materials,
+                    vec![
+                        (
+                            ItemMenuOption::Reroll,
+                            reroll.to_string(),
+                        ),
+                        (
+                            ItemMenuOption::Skip,
+                            "Skip (+1 life)".to_string(),
+                        ),
+                    ],
+                )
+            });
+    }
+
+    // every stat that's different between
+    // before and after as "name old -> new"
+    fn changes(
+        before: &item::Config,
+        after: &item::Config,
+    ) -> Vec<String> {
+        // whole numbers don't need decimal places
+        let show = |value: f32| {
+            if value.fract() == 0.0 {
+                format!("{:.0}", value)
+            } else {
+                format!("{:.2}", value)
+            }
+        };
+        before
+            .stats()
+            .into_iter()
+            .zip(after.stats())
+            .filter(|((_, old), (_, new))| {
+                (old - new).abs() > 0.001
+            })
+            .map(|((name, old), (_, new))| {
+                format!(
+                    "{} {} -> {}",
+                    name,
+                    show(old),
+                    show(new)
+                )
+            })
+            .collect()
     }
 
     // spawns in a button
@@ -542,9 +1152,41 @@ impl ItemMenu {
     // as a child of parent
     fn spawn_button(
         parent: &mut ChildBuilder,
-        item: Box<dyn item::Item>,
+        item: &item::ItemDef,
+        synergies: &[&item::Synergy],
+        changes: &[String],
         materials: &asset::Materials,
     ) {
+        let text =
+            |value: String, font_size: f32| TextSection {
+                value,
+                style: TextStyle {
+                    font: materials.main_font.clone(),
+                    font_size,
+                    color: Color::rgb(0.0, 0.0, 0.0),
+                },
+            };
+        let mut sections = vec![
+            text(format!("{}\n", item.name), 14.0),
+            // how rare it is to be offered
+            text(format!("{}\n", item.rarity.name()), 10.0),
+            text(item.desc.clone(), 10.0),
+        ];
+        if !changes.is_empty() {
+            sections.push(text(
+                format!("\n\n{}", changes.join("\n")),
+                10.0,
+            ));
+        }
+        for synergy in synergies {
+            sections.push(text(
+                format!(
+                    "\n\nCompletes {}: {}",
+                    synergy.name, synergy.desc
+                ),
+                12.0,
+            ));
+        }
         parent
             .spawn_bundle(ButtonBundle {
                 style: Style {
@@ -563,42 +1205,14 @@ impl ItemMenu {
                 material: materials.player_gun.clone(),
                 ..Default::default()
             })
-            .insert(ItemMenuButton { item: item.id() })
+            .insert(ItemMenuButton {
+                item: item.id.clone(),
+            })
             .with_children(|parent| {
                 // item name and description text
                 parent.spawn_bundle(TextBundle {
                     text: Text {
-                        sections: vec![
-                            TextSection {
-                                value: item
-                                    .name()
-                                    
-                                    + "\n",
-                                style: TextStyle {
-                                    font: materials
-                                        .main_font
-                                        .clone(),
-                                    font_size: 14.0,
-                                    color: Color::rgb(
-                                        0.0, 0.0, 0.0,
-                                    ),
-                                },
-                            },
-                            TextSection {
-                                value: item
-                                    .desc()
-                                    ,
-                                style: TextStyle {
-                                    font: materials
-                                        .main_font
-                                        .clone(),
-                                    font_size: 10.0,
-                                    color: Color::rgb(
-                                        0.0, 0.0, 0.0,
-                                    ),
-                                },
-                            },
-                        ],
+                        sections,
                         alignment: TextAlignment {
                             vertical: VerticalAlign::Top,
                             horizontal:
@@ -627,13 +1241,23 @@ impl ItemMenu {
     }
 
     // handles interactions with buttons
-    pub fn interactions(
+    #[allow(clippy::too_many_arguments)]
+    fn interactions(
         mut app_state: ResMut<State<state::GameState>>,
         mut items: ResMut<item::ItemManager>,
+        mut lives: ResMut<player::Lives>,
+        mut offer: ResMut<ItemOffer>,
+        table: Res<item::LootTable>,
+        difficulty: Res<grid::Difficulty>,
+        run_seed: Res<seed::RunSeed>,
         query: Query<
             (&Interaction, &ItemMenuButton),
             (Changed<Interaction>, With<Button>),
         >,
+        options: Query<
+            (&Interaction, &ItemMenuOption),
+            (Changed<Interaction>, With<Button>),
+        >,
     ) {
         for (interaction, button) in query.iter() {
             // if a given button is pressed then
@@ -646,7 +1270,188 @@ impl ItemMenu {
                         state::GameState::LoadingLevel,
                     )
                     .unwrap();
+                return;
             }
         }
+        for (interaction, option) in options.iter() {
+            if *interaction != Interaction::Clicked {
+                continue;
+            }
+            match option {
+                // can't spend the last life
+                ItemMenuOption::Reroll if lives.0 > 1 => {
+                    lives.0 -= 1;
+                    *offer = ItemOffer::new(
+                        &table,
+                        &run_seed,
+                        difficulty.level(),
+                        offer.rerolls + 1,
+                    );
+                }
+                ItemMenuOption::Reroll => (),
+                ItemMenuOption::Skip => {
+                    lives.0 += 1;
+                    app_state
+                        .overwrite_set(
+                            state::GameState::LoadingLevel,
+                        )
+                        .unwrap();
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// spawns a full screen column of buttons each holding
+// the given component so the menu can tell them apart
+fn spawn_button_list<M: Component, B: Component>(
+    commands: &mut Commands,
+    materials: &asset::Materials,
+    menu: M,
+    buttons: Vec<(B, String)>,
+) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(
+                    Val::Percent(100.0),
+                    Val::Percent(100.0),
+                ),
+                // top to bottom
+                flex_direction:
+                    FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: materials.tile_empty.clone(),
+            ..Default::default()
+        })
+        .insert(menu)
+        .with_children(|parent| {
+            spawn_buttons(parent, materials, buttons)
+        });
+}
+
+// spawns the buttons for a list inside parent
+fn spawn_buttons<B: Component>(
+    parent: &mut ChildBuilder,
+    materials: &asset::Materials,
+    buttons: Vec<(B, String)>,
+) {
+    for (button, text) in buttons {
+        parent
+            .spawn_bundle(ButtonBundle {
+                style: Style {
+                    size: Size::new(
+                        Val::Percent(60.0),
+                        Val::Px(40.0),
+                    ),
+                    margin: Rect::all(Val::Px(2.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: materials.button_normal.clone(),
+                ..Default::default()
+            })
+            .insert(button)
+            .insert(widget::Widget)
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        text,
+                        TextStyle {
+                            font: materials
+                                .main_font
+                                .clone(),
+                            font_size: 16.0,
+                            color: Color::rgb(
+                                0.9, 0.9, 0.9,
+                            ),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                });
+            });
+    }
+}
+
+// leaves a menu that was opened on top of another
+// one, or goes back to the main menu if it wasn't
+fn back(game_state: &mut State<state::GameState>) {
+    if game_state.pop().is_err() {
+        game_state.set(state::GameState::MainMenu).unwrap();
+    }
+}
+
+// gamepad navigation for the menus
+mod focus;
+
+// buttons that react to being used
+mod widget;
+
+mod controls;
+
+mod settings;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> item::LootTable {
+        item::LootTable::new(&item::ItemCatalogue::parse(
+            r#"(items: [
+                (id: "A", name: "A", desc: "", icon: "A",
+                    rarity: Common),
+                (id: "B", name: "B", desc: "", icon: "B",
+                    rarity: Common),
+                (id: "C", name: "C", desc: "", icon: "C",
+                    rarity: Uncommon),
+                (id: "D", name: "D", desc: "", icon: "D",
+                    rarity: Rare),
+            ])"#,
+        ))
+    }
+
+    // a saved run's rerolls have to bring back
+    // exactly the offer that was on screen
+    #[test]
+    fn rerolls_repeat() {
+        let table = table();
+        let run_seed = seed::RunSeed::new(1234);
+        for rerolls in 0..5 {
+            assert_eq!(
+                ItemOffer::new(
+                    &table, &run_seed, 3, rerolls
+                )
+                .items,
+                ItemOffer::new(
+                    &table, &run_seed, 3, rerolls
+                )
+                .items
+            );
+        }
+    }
+
+    #[test]
+    fn rerolls_change_the_offer() {
+        let table = table();
+        let run_seed = seed::RunSeed::new(1234);
+        let offers = (0..5)
+            .map(|rerolls| {
+                ItemOffer::new(
+                    &table, &run_seed, 3, rerolls,
+                )
+                .items
+            })
+            .collect::<Vec<_>>();
+        // not every reroll has to be different but
+        // they can't all be the same
+        assert!(offers
+            .iter()
+            .any(|offer| *offer != offers[0]));
     }
 }
@@ -0,0 +1,162 @@
+use crate::{
+    actions::{Action, Binding, InputMap},
+    asset, state,
+};
+use bevy::prelude::{DespawnRecursiveExt, *};
+
+// the screen for changing which buttons do what
+pub struct ControlsMenu;
+
+// what each button on the controls screen does
+#[derive(Debug, Clone, Copy)]
+enum ControlsButton {
+    Rebind(Action),
+    Reset,
+    Back,
+}
+
+// the action waiting for a button to be pressed
+// to bind it to
+#[derive(Debug, Default)]
+struct Rebinding(Option<Action>);
+
+impl ControlsMenu {
+    pub fn build(app: &mut AppBuilder) {
+        app.init_resource::<Rebinding>()
+            .add_system_set(
+                SystemSet::on_update(
+                    state::GameState::Controls,
+                )
+                .with_system(ControlsMenu::refresh.system())
+                .with_system(
+                    ControlsMenu::interactions.system(),
+                )
+                .with_system(
+                    ControlsMenu::capture.system(),
+                ),
+            )
+            .add_system(state::GameState::despawn::<
+                ControlsMenu,
+            >(
+                state::GameState::Controls
+            ));
+    }
+
+    // (re)spawns the screen whenever the
+    // bindings it shows change
+    fn refresh(
+        mut commands: Commands,
+        materials: Res<asset::Materials>,
+        map: Res<InputMap>,
+        rebinding: Res<Rebinding>,
+        menu: Query<Entity, With<ControlsMenu>>,
+    ) {
+        let spawned = menu.iter().next().is_some();
+        if spawned
+            && !map.is_changed()
+            && !rebinding.is_changed()
+        {
+            return;
+        }
+        for entity in menu.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        // every action and then the other buttons
+        let buttons = Action::ALL
+            .iter()
+            .map(|action| {
+                let bound = match rebinding.0 {
+                    Some(waiting) if waiting == *action => {
+                        "press a button...".to_string()
+                    }
+                    _ => map
+                        .bindings(*action)
+                        .iter()
+                        .map(Binding::name)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                };
+                (
+                    ControlsButton::Rebind(*action),
+                    format!("{}: {}", action.name(), bound),
+                )
+            })
+            .chain(vec![
+                (
+                    ControlsButton::Reset,
+                    "Reset".to_string(),
+                ),
+                (ControlsButton::Back, "Back".to_string()),
+            ]);
+        super::spawn_button_list(
+            &mut commands,
+            &materials,
+            ControlsMenu,
+            buttons.collect(),
+        );
+    }
+
+    // handles the buttons being clicked
+    fn interactions(
+        mut game_state: ResMut<State<state::GameState>>,
+        mut map: ResMut<InputMap>,
+        mut rebinding: ResMut<Rebinding>,
+        query: Query<
+            (&Interaction, &ControlsButton),
+            (Changed<Interaction>, With<Button>),
+        >,
+    ) {
+        for (interaction, button) in query.iter() {
+            if *interaction != Interaction::Clicked {
+                continue;
+            }
+            match button {
+                // waiting for the next button press
+                ControlsButton::Rebind(action) => {
+                    rebinding.0 = Some(*action)
+                }
+                ControlsButton::Reset => {
+                    *map = InputMap::default();
+                    map.save();
+                }
+                ControlsButton::Back => {
+                    rebinding.0 = None;
+                    super::back(&mut game_state);
+                }
+            }
+        }
+    }
+
+    // binds the next button pressed to the
+    // action that's waiting for one
+    // (escape cancels instead of being bound so there's
+    // always a way out without changing anything)
+    fn capture(
+        mut map: ResMut<InputMap>,
+        mut rebinding: ResMut<Rebinding>,
+        keys: Res<Input<KeyCode>>,
+        mouse_buttons: Res<Input<MouseButton>>,
+        gamepad_buttons: Res<Input<GamepadButton>>,
+    ) {
+        // skipping the frame the rebind was asked for
+        // so the click that asked isn't bound
+        if rebinding.is_changed() {
+            return;
+        }
+        let action = match rebinding.0 {
+            Some(action) => action,
+            None => return,
+        };
+        if let Some(binding) = Binding::just_pressed(
+            &keys,
+            &mouse_buttons,
+            &gamepad_buttons,
+        ) {
+            if binding != Binding::Key(KeyCode::Escape) {
+                map.bind(action, binding);
+                map.save();
+            }
+            rebinding.0 = None;
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::actions::{Action, ActionSystem, Actions};
+use bevy::prelude::*;
+
+// marks the button the menu actions have selected so
+// menus can be used with a gamepad or the keyboard
+pub struct MenuFocus;
+
+// marks a button that was clicked by confirming rather
+// than with the mouse, bevy only lets go of a click when
+// the mouse button is released so it's done by hand
+struct Confirmed;
+
+impl MenuFocus {
+    // how much bigger the focused button is drawn
+    const SCALE: f32 = 1.1;
+
+    pub fn build(app: &mut AppBuilder) {
+        // always running as every menu has buttons
+        // (before the menus so they all see the click
+        // the same frame and after so it's let go)
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            MenuFocus::navigate
+                .system()
+                .after(ActionSystem),
+        )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            MenuFocus::release.system(),
+        );
+    }
+
+    // lets go of a confirmed click once the menus
+    // have had the frame to react to it (the focus
+    // still shows it as hovered)
+    fn release(
+        mut commands: Commands,
+        mut buttons: Query<
+            (Entity, &mut Interaction),
+            With<Confirmed>,
+        >,
+    ) {
+        for (entity, mut interaction) in buttons.iter_mut()
+        {
+            if *interaction == Interaction::Clicked {
+                *interaction = Interaction::None;
+            }
+            commands.entity(entity).remove::<Confirmed>();
+        }
+    }
+
+    // moves the focus towards whichever way was pressed
+    // (the d-pad or arrow keys by default) and clicks the
+    // focused button on confirm
+    fn navigate(
+        mut commands: Commands,
+        actions: Res<Actions>,
+        mut buttons: Query<
+            (
+                Entity,
+                &GlobalTransform,
+                &mut Transform,
+                &mut Interaction,
+                Option<&MenuFocus>,
+            ),
+            With<Button>,
+        >,
+    ) {
+        // the direction that was just pressed in
+        // ui positions go up the screen like the world does
+        let mut dir = Vec2::ZERO;
+        if actions.just_pressed(Action::MenuUp) {
+            dir.y += 1.0
+        }
+        if actions.just_pressed(Action::MenuDown) {
+            dir.y -= 1.0
+        }
+        if actions.just_pressed(Action::MenuLeft) {
+            dir.x -= 1.0
+        }
+        if actions.just_pressed(Action::MenuRight) {
+            dir.x += 1.0
+        }
+        let confirm =
+            actions.just_pressed(Action::MenuConfirm);
+        if dir == Vec2::ZERO && !confirm {
+            return;
+        }
+        let focused = buttons
+            .iter_mut()
+            .find(|(.., focus)| focus.is_some())
+            .map(|(entity, global, ..)| {
+                (entity, global.translation.truncate())
+            });
+        let next = match focused {
+            // pressing the focused button
+            Some((entity, _)) if confirm => {
+                if let Ok((.., mut interaction, _)) =
+                    buttons.get_mut(entity)
+                {
+                    *interaction = Interaction::Clicked;
+                    commands
+                        .entity(entity)
+                        .insert(Confirmed);
+                }
+                return;
+            }
+            // the closest button in the direction pressed
+            Some((_, from)) => buttons
+                .iter_mut()
+                .map(|(entity, global, ..)| {
+                    (
+                        entity,
+                        global.translation.truncate()
+                            - from,
+                    )
+                })
+                .filter(|(_, offset)| offset.dot(dir) > 0.0)
+                .min_by(|(_, a), (_, b)| {
+                    a.length_squared()
+                        .partial_cmp(&b.length_squared())
+                        .unwrap()
+                })
+                .map(|(entity, _)| entity),
+            // nothing is focused yet so start
+            // at the top left button
+            None => buttons
+                .iter_mut()
+                .map(|(entity, global, ..)| {
+                    (entity, global.translation.truncate())
+                })
+                .min_by(|(_, a), (_, b)| {
+                    (a.x - a.y)
+                        .partial_cmp(&(b.x - b.y))
+                        .unwrap()
+                })
+                .map(|(entity, _)| entity),
+        };
+        let next = match next {
+            Some(next) => next,
+            // nothing that way so stay put
+            None => return,
+        };
+        // moving the focus and making it
+        // obvious which button has it
+        for (entity, _, mut transform, _, focus) in
+            buttons.iter_mut()
+        {
+            if entity == next {
+                commands.entity(entity).insert(MenuFocus);
+                transform.scale = Vec3::splat(Self::SCALE);
+            } else if focus.is_some() {
+                commands
+                    .entity(entity)
+                    .remove::<MenuFocus>();
+                transform.scale = Vec3::ONE;
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    asset,
+    settings::{Palette, Settings, WindowSetting},
+    state,
+};
+use bevy::prelude::{DespawnRecursiveExt, *};
+
+// the screen for changing the settings
+pub struct SettingsMenu;
+
+// what each button on the settings screen does
+#[derive(Debug, Clone, Copy)]
+enum SettingsButton {
+    Window,
+    Countdown,
+    Palette,
+    Controls,
+    Back,
+}
+
+impl SettingsMenu {
+    pub fn build(app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(
+                state::GameState::Settings,
+            )
+            .with_system(SettingsMenu::refresh.system())
+            .with_system(
+                SettingsMenu::interactions.system(),
+            ),
+        )
+        .add_system(
+            state::GameState::despawn::<SettingsMenu>(
+                state::GameState::Settings,
+            ),
+        )
+        // hiding it whilst the controls are open
+        // (refresh brings it back afterwards)
+        .add_system(
+            state::GameState::despawn_on_pause::<
+                SettingsMenu,
+            >(state::GameState::Settings),
+        );
+    }
+
+    // (re)spawns the screen whenever the
+    // settings it shows change
+    fn refresh(
+        mut commands: Commands,
+        materials: Res<asset::Materials>,
+        settings: Res<Settings>,
+        menu: Query<Entity, With<SettingsMenu>>,
+    ) {
+        if menu.iter().next().is_some()
+            && !settings.is_changed()
+        {
+            return;
+        }
+        for entity in menu.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        super::spawn_button_list(
+            &mut commands,
+            &materials,
+            SettingsMenu,
+            vec![
+                (
+                    SettingsButton::Window,
+                    format!(
+                        "Window: {}",
+                        settings.window.name()
+                    ),
+                ),
+                (
+                    SettingsButton::Countdown,
+                    format!(
+                        "Countdown: {}s",
+                        settings.countdown
+                    ),
+                ),
+                (
+                    SettingsButton::Palette,
+                    format!(
+                        "Colors: {}",
+                        settings.palette.name()
+                    ),
+                ),
+                (
+                    SettingsButton::Controls,
+                    "Controls".to_string(),
+                ),
+                (SettingsButton::Back, "Back".to_string()),
+            ],
+        );
+    }
+
+    // handles the buttons being clicked, each
+    // setting cycles to its next option
+    fn interactions(
+        mut game_state: ResMut<State<state::GameState>>,
+        mut settings: ResMut<Settings>,
+        query: Query<
+            (&Interaction, &SettingsButton),
+            (Changed<Interaction>, With<Button>),
+        >,
+    ) {
+        // the option after current in options
+        // wrapping back round to the first
+        fn next<T: Copy + PartialEq>(
+            options: &[T],
+            current: T,
+        ) -> T {
+            let index = options
+                .iter()
+                .position(|option| *option == current)
+                .map_or(0, |index| index + 1);
+            options[index % options.len()]
+        }
+        for (interaction, button) in query.iter() {
+            if *interaction != Interaction::Clicked {
+                continue;
+            }
+            match button {
+                SettingsButton::Window => {
+                    settings.window = next(
+                        &WindowSetting::ALL,
+                        settings.window,
+                    )
+                }
+                SettingsButton::Countdown => {
+                    settings.countdown = next(
+                        &Settings::COUNTDOWNS,
+                        settings.countdown,
+                    )
+                }
+                SettingsButton::Palette => {
+                    settings.palette = next(
+                        &Palette::ALL,
+                        settings.palette,
+                    )
+                }
+                // opening the controls on top of this
+                // so that back comes back here
+                SettingsButton::Controls => {
+                    game_state
+                        .push(state::GameState::Controls)
+                        .unwrap();
+                    continue;
+                }
+                SettingsButton::Back => {
+                    super::back(&mut game_state);
+                    continue;
+                }
+            }
+            settings.save();
+        }
+    }
+}
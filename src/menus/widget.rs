@@ -0,0 +1,53 @@
+use super::focus::MenuFocus;
+use crate::asset;
+use bevy::prelude::*;
+
+// marks a button spawned by spawn_button_list so that
+// it lights up when it's hovered, focused or pressed
+pub struct Widget;
+
+impl Widget {
+    pub fn build(app: &mut AppBuilder) {
+        // always running as most menus are made of widgets
+        app.add_system(Widget::feedback.system());
+    }
+
+    // colors every widget by what's happening to it
+    // (focus counts as hovering so the keyboard and
+    // gamepad get the same feedback as the mouse)
+    fn feedback(
+        materials: Res<asset::Materials>,
+        mut widgets: Query<
+            (
+                &Interaction,
+                Option<&MenuFocus>,
+                &mut Handle<ColorMaterial>,
+            ),
+            With<Widget>,
+        >,
+    ) {
+        for (interaction, focus, mut material) in
+            widgets.iter_mut()
+        {
+            let next = match interaction {
+                Interaction::Clicked => {
+                    &materials.button_pressed
+                }
+                Interaction::Hovered => {
+                    &materials.button_hovered
+                }
+                Interaction::None if focus.is_some() => {
+                    &materials.button_hovered
+                }
+                Interaction::None => {
+                    &materials.button_normal
+                }
+            };
+            // only writing when it's different so the
+            // material isn't marked as changed every frame
+            if *material != *next {
+                *material = next.clone();
+            }
+        }
+    }
+}
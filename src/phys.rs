@@ -1,3 +1,14 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+// disables gravity by setting
+// it to zero
+pub fn disable_gravity(
+    mut rapier_cofig: ResMut<RapierConfiguration>,
+) {
+    rapier_cofig.gravity = Vec2::ZERO.into();
+}
+
 macro_rules! group {
     ($name:ident = $memberships:expr, $filter:expr) => {
         pub fn $name() -> InteractionGroups {
@@ -23,17 +34,33 @@ pub mod masks {
     const PLAYER_BULLET: u32 = 0b100;
     const ENEMY: u32 = 0b1000;
     const ENEMY_VISION: u32 = 0b10000;
+    const ENEMY_BULLET: u32 = 0b100000;
 
-    group!(player = PLAYER, WALL + ENEMY + ENEMY_VISION);
+    group!(
+        player = PLAYER,
+        WALL + ENEMY + ENEMY_VISION + ENEMY_BULLET
+    );
     group!(
         wall = WALL,
-        PLAYER + PLAYER_BULLET + ENEMY + ENEMY_VISION
+        PLAYER
+            + PLAYER_BULLET
+            + ENEMY
+            + ENEMY_VISION
+            + ENEMY_BULLET
     );
     group!(none = NONE, NONE);
     group!(player_bullet = PLAYER_BULLET, WALL + ENEMY);
+    // used as the solver groups so piercing bullets
+    // still touch enemies but only bounce off walls
+    group!(piercing_bullet = PLAYER_BULLET, WALL);
     group!(
         enemy = ENEMY,
         PLAYER_BULLET + PLAYER + WALL + ENEMY
     );
     group!(enemy_vision = ENEMY_VISION, WALL + PLAYER);
+    // enemy bullets fly straight through other enemies
+    group!(enemy_bullet = ENEMY_BULLET, WALL + PLAYER);
+    // used as the solver groups so enemy bullets still
+    // hurt the player without shoving them about
+    group!(enemy_bullet_solver = ENEMY_BULLET, WALL);
 }
@@ -2,7 +2,9 @@ use bevy::{app::Events, prelude::*};
 use bevy_rapier2d::prelude::*;
 // importing state module here
 // importing asset module here
-use crate::{asset, enemies, grid, item, phys, state};
+use crate::{
+    asset, clock, cooldown, grid, health, item, phys, state,
+};
 // this class has no internal data and only
 // "inherits" (not how Rust's traits (abstract base classes)
 // actually work) Plugin which has the method build
@@ -17,9 +19,8 @@ impl Plugin for PlayerPlugin {
             // frame when the game state is GameState::InLevel
             SystemSet::on_update(state::GameState::InLevel)
                 .with_system(Player::movement.system())
-                .with_system(
-                    Player::detect_enemy_hits.system(),
-                ),
+                .with_system(Player::dash.system())
+                .with_system(Player::check_health.system()),
         )
         .add_system_set(
             // this means Player::spawn will run when
@@ -29,19 +30,32 @@ impl Plugin for PlayerPlugin {
                 .with_system(Player::spawn.system()),
         )
         .add_system(Lives::on_hit.system())
+        .add_system(Lives::reset.system())
         .add_system(state::GameState::despawn::<Player>(
             state::GameState::InLevel,
         ))
         .add_plugin(gun::GunPlugin)
-        .init_resource::<Lives>();
+        .init_resource::<Lives>()
+        // what the player is trying to do, filled in
+        // by whatever is controlling the player
+        .init_resource::<input::PlayerInput>();
     }
 }
 
 // this struct holds all the player's data
-// (for now there isn't any)
 #[derive(Debug, Clone)]
 pub struct Player {
+    // the fastest the player can walk
     speed: f32,
+    // how quickly the player gets up to speed
+    acceleration: f32,
+    // how quickly the player stops
+    deceleration: f32,
+    // how far a dash goes
+    dash_distance: f32,
+    // which way the player is dashing
+    // and for how many more seconds
+    dashing: Option<(Vec2, f32)>,
 }
 
 // this is how Rust denotes
@@ -50,6 +64,15 @@ pub struct Player {
 // it allows for a structs methods
 // to be defined in more than one place
 impl Player {
+    // how many seconds the player can't be hurt
+    // for after getting hit
+    const IFRAMES: f32 = 1.0;
+    // how many seconds a dash lasts for
+    const DASH_TIME: f32 = 0.15;
+    // how many seconds after a dash starts the player
+    // can't be hurt for
+    const DASH_IFRAMES: f32 = 0.25;
+
     // spawns in the player
     // ResMut<Assets<ColorMaterial>>> is a mutable reference to the
     // colour material asset server which manages loading materials
@@ -63,7 +86,7 @@ impl Player {
         // spawns in the player
         commands
             .spawn_bundle(PlayerBundle::new(
-                grid.player.unwrap().to_world(),
+                grid.player.unwrap().to_world(grid.size()),
                 config.player.clone(),
             ))
             // adds the gun as child of the
@@ -75,95 +98,103 @@ impl Player {
             });
     }
     // moves the player around
-    // keys tells me what keys on the keyboard are pressed at any given time
+    // input tells me which way the player is trying to go
+    // and the player speeds up and slows down towards
+    // that rather than changing speed instantly
     fn movement(
+        clock: Res<clock::Clock>,
         mut query: Query<(&mut RigidBodyVelocity, &Player)>,
-        keys: Res<Input<KeyCode>>,
+        input: Res<input::PlayerInput>,
     ) {
-        let mut dir = Vec2::ZERO;
-        // move up
-        if keys.pressed(KeyCode::W) {
-            dir.y += 1.0
-        }
-        // move left
-        if keys.pressed(KeyCode::A) {
-            dir.x -= 1.0
-        }
-        // move down
-        if keys.pressed(KeyCode::S) {
-            dir.y -= 1.0
-        }
-        // move right
-        if keys.pressed(KeyCode::D) {
-            dir.x += 1.0
-        }
-
-        // this gets a mutable reference to the players transform
+        // this gets a mutable reference to the players velocity
         // and a immutable reference to Player
         // it will crash if this is run when there is not 1 player
         let (mut vel, player) = query.single_mut().unwrap();
-        // times direction by speed to get veloctiy
-        // normalising it makes it's magnitude
-        // unit length. then multiplying by
-        // player.speed garuntees its the right length
-        // let vel = dir.normalize_or_zero() * player.speed;
-        // or the faster version
-        // because it can make a bunch of
-        // assumptions that an external call
-        // can't make as its for more generalised
-        // usage whereas this is just faster in
-        // this particular case
-        // also in Rust the last line of a
-        // code block is implicitly returned
-        let new_vel = if dir.x == 0.0 || dir.y == 0.0 {
-            // if it's got one dimension that's 0
-            // then its already normalised
-            dir * player.speed
+        // dashing takes over the player's movement
+        if player.dashing.is_some() {
+            return;
+        }
+        // analog sticks can be pushed part way so how far
+        // they're pushed is kept, but nothing (like a
+        // diagonal on the keyboard) goes past full speed
+        let dir = if input.movement.length_squared() > 1.0 {
+            input.movement.normalize()
+        } else {
+            input.movement
+        };
+        let target = dir * player.speed;
+        let current: Vec2 = vel.linvel.into();
+        // speeding up whilst moving and slowing
+        // down when letting go
+        let rate = if dir == Vec2::ZERO {
+            player.deceleration
+        } else {
+            player.acceleration
+        };
+        // moving the velocity towards the target without
+        // going past it
+        let step = rate * clock.delta_seconds();
+        let diff = target - current;
+        let new_vel = if diff.length() <= step {
+            target
         } else {
-            // sqrt(1^2 + 1^2) / (1 + 1)
-            // = sqrt(2) / 2
-            // as thats total magnitude
-            // (pythagoras so root 2)
-            // divided by the fraction the x and y
-            // components represent but here they're
-            // both always 1 so there 1/2
-            const NORMALIZE_FACTOR: f32 = 0.707_106_77;
-            dir * NORMALIZE_FACTOR * player.speed
+            current + diff.normalize() * step
         };
-        // adding the current velocity to the player's translation
-        // need to extend it so it has a z which represents what
-        // layer the player is on
         vel.linvel = new_vel.into();
     }
 
-    // this detects any collisions between the player
-    // and enemies and if there are any sends
-    // an event to update anything that should
-    // react to the player being hit
-    pub fn detect_enemy_hits(
-        mut contact_events: EventReader<ContactEvent>,
-        player: Query<Entity, With<Player>>,
-        enemies: Query<(), With<enemies::Enemy>>,
+    // launches the player the way they're moving when
+    // they dash if it's cooled down, not being able to be
+    // hurt for a moment
+    fn dash(
+        clock: Res<clock::Clock>,
+        input: Res<input::PlayerInput>,
+        mut query: Query<(
+            &mut RigidBodyVelocity,
+            &mut Player,
+            &mut health::Health,
+            &mut cooldown::Cooldown,
+        )>,
+    ) {
+        let (mut vel, mut player, mut health, mut cooldown) =
+            query.single_mut().unwrap();
+        if let Some((dir, left)) = player.dashing {
+            let left = left - clock.delta_seconds();
+            player.dashing = if left > 0.0 {
+                Some((dir, left))
+            } else {
+                None
+            };
+            return;
+        }
+        // can't dash without a direction to go in
+        let dir = input.movement.normalize_or_zero();
+        if !input.dash
+            || !cooldown.is_over()
+            || dir == Vec2::ZERO
+        {
+            return;
+        }
+        cooldown.reset();
+        health.make_invulnerable(Player::DASH_IFRAMES);
+        player.dashing = Some((dir, Player::DASH_TIME));
+        // fast enough to cover the distance in time
+        vel.linvel = (dir * player.dash_distance
+            / Player::DASH_TIME)
+            .into();
+    }
+
+    // when the player runs out of health for this level
+    // sends an event to update anything that should
+    // react to the player losing a life
+    pub fn check_health(
+        player: Query<&health::Health, With<Player>>,
         mut game_events: EventWriter<state::GameEvent>,
     ) {
-        for event in contact_events.iter() {
-            if let ContactEvent::Started(h1, h2) = event {
-                // getting the entities related to
-                // the physics handles of the two contacting things
-                let (e1, e2) = (h1.entity(), h2.entity());
-                // the engine gives no particular order
-                // so test both orders
-                for (plr, enemy) in [(e1, e2), (e2, e1)] {
-                    if player.get(plr).is_ok()
-                        && enemies.get(enemy).is_ok()
-                    {
-                        // the two contacts were a player
-                        // and enemy so the player was hit
-                        game_events.send(
-                            state::GameEvent::PlayerHit,
-                        );
-                    }
-                }
+        if let Ok(health) = player.single() {
+            if health.is_dead() {
+                game_events
+                    .send(state::GameEvent::PlayerHit);
             }
         }
     }
@@ -174,6 +205,10 @@ impl Player {
 struct PlayerBundle {
     // this holds all the player's data
     player: Player,
+    // the player's health for this level
+    health: health::Health,
+    // time until the player can dash again
+    dash: cooldown::Cooldown,
     // this unpacks the SpriteBundle of components
     // and and add all of them to the player
     #[bundle]
@@ -199,7 +234,25 @@ impl PlayerBundle {
         PlayerBundle {
             player: Player {
                 speed: builder.speed,
+                acceleration: builder.acceleration,
+                deceleration: builder.deceleration,
+                dash_distance: builder.dash_distance,
+                dashing: None,
+            },
+            // the player can dash straight away
+            dash: {
+                let mut dash = cooldown::Cooldown::new(
+                    Some(builder.dash_cooldown),
+                );
+                dash.finish();
+                dash
             },
+            // every level starts with full health
+            health: health::Health::new(
+                health::Team::Player,
+                builder.health,
+                Player::IFRAMES,
+            ),
             sprite: SpriteBundle {
                 // makes the sprite white
                 material: builder.material.clone(),
@@ -250,25 +303,51 @@ impl PlayerBundle {
 // items and the player's behaviour
 #[derive(Debug, Clone)]
 pub struct PlayerBuilder {
+    // the player's top speed
     pub speed: f32,
+    // how much speed the player gains a second
+    // whilst moving
+    pub acceleration: f32,
+    // how much speed the player loses a second
+    // once they let go
+    pub deceleration: f32,
     pub material: Handle<ColorMaterial>,
     pub size: Vec2,
+    // how much damage the player can take
+    // before losing a life
+    pub health: f32,
+    // how far a dash goes
+    pub dash_distance: f32,
+    // how many seconds between dashes
+    pub dash_cooldown: f32,
 }
 
-impl FromWorld for PlayerBuilder {
+impl PlayerBuilder {
     // returns a default version of the player builder
-    fn from_world(world: &mut World) -> Self {
-        let materials = world
-            .get_resource::<asset::Materials>()
-            .unwrap();
+    pub fn new(materials: &asset::Materials) -> Self {
         Self {
             speed: 200.0,
+            acceleration: 2000.0,
+            deceleration: 2500.0,
             material: materials.player_body.clone(),
             size: Vec2::new(20., 20.),
+            health: 3.0,
+            dash_distance: 80.0,
+            dash_cooldown: 1.0,
         }
     }
 }
 
+impl FromWorld for PlayerBuilder {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(
+            world
+                .get_resource::<asset::Materials>()
+                .unwrap(),
+        )
+    }
+}
+
 // this stores how many lives the player has
 #[derive(Debug)]
 pub struct Lives(pub u32);
@@ -295,11 +374,10 @@ impl Lives {
             // if the player has one life then
             // this is their last life so game
             // over
+            // (the lives are reset by Lives::reset)
             if lives.0 <= 1 {
                 game_events
                     .send(state::GameEvent::GameOver);
-                // resetting the player's lives counter
-                *lives = Lives::default();
             } else {
                 // otherwise subtract one
                 // from the player's lives
@@ -307,11 +385,26 @@ impl Lives {
             }
         }
     }
+
+    // resets the player's lives when the game is over
+    // however the run ended
+    pub fn reset(
+        mut lives: ResMut<Lives>,
+        mut events: EventReader<state::GameEvent>,
+    ) {
+        if events.iter().any(|event| {
+            matches!(event, state::GameEvent::GameOver)
+        }) {
+            *lives = Lives::default();
+        }
+    }
 }
 
 // adding submodules to hold code for
 // the gun and bullets
 mod gun;
-pub use gun::GunBuilder;
+pub use gun::{Gun, GunBuilder};
+
+pub mod input;
 
 pub mod bullet;
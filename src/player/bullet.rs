@@ -1,12 +1,283 @@
-use crate::{cooldown, just_spawned, phys};
+use crate::{
+    clock, cooldown, enemies, health, just_spawned, phys,
+};
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 use std::f32::consts::PI;
 
-// marker struct to make the bullet more
-// identifiable, in future will be used
-// for something probably
-pub struct Bullet;
+// marks the player's bullets with how much they
+// hurt, which is dealt by the gun when they hit
+// rather than on contact so that each bullet
+// only hurts an enemy once
+pub struct Bullet {
+    pub damage: health::Damage,
+    // which pull of the trigger (or step of a burst)
+    // this came from, so that a shot of many bullets
+    // only counts as one hit for the stats
+    pub shot: u32,
+}
+
+impl Bullet {
+    // spawns a bullet with every behaviour the gun
+    // gives it, hit is any enemies it should
+    // ignore as it's already gone through them
+    pub fn spawn(
+        commands: &mut Commands,
+        builder: &super::GunBuilder,
+        shot: u32,
+        angle: f32,
+        pos: Vec2,
+        hit: Vec<Entity>,
+    ) -> Entity {
+        let mut bundle =
+            BulletBundle::new(builder.clone(), angle, pos);
+        bundle.bullet.shot = shot;
+        // bullets that can be inside an enemy without
+        // breaking shouldn't get knocked back by it
+        let piercing =
+            builder.pierce > 0 || !hit.is_empty();
+        if piercing {
+            bundle.collider.flags.solver_groups =
+                phys::masks::piercing_bullet();
+        }
+        let mut bullet = commands.spawn_bundle(bundle);
+        if piercing {
+            bullet.insert(Pierce {
+                left: builder.pierce,
+                hit,
+            });
+        }
+        if builder.split > 0 {
+            bullet.insert(Split {
+                fragments: builder.split,
+            });
+        }
+        if builder.homing > 0.0 {
+            bullet.insert(Homing {
+                turn_rate: builder.homing,
+            });
+        }
+        if builder.explosion > 0.0 {
+            bullet.insert(Explode {
+                radius: builder.explosion,
+            });
+        }
+        bullet.id()
+    }
+}
+
+// how a bullet speeds up and slows down
+// whilst it's flying
+pub struct Flight {
+    // how much speed it gains a second
+    acceleration: f32,
+    // the fraction of its speed it loses a second
+    drag: f32,
+}
+
+impl Flight {
+    // the speed a bullet will be going after delta
+    // seconds of speeding up and slowing down
+    pub fn speed_after(
+        speed: f32,
+        acceleration: f32,
+        drag: f32,
+        delta: f32,
+    ) -> f32 {
+        ((speed + acceleration * delta)
+            * (1.0 - drag * delta).max(0.0))
+        .max(0.0)
+    }
+
+    // speeds up or slows down every bullet along the
+    // way it's going (its lifetime is what ends it)
+    pub fn update(
+        clock: Res<clock::Clock>,
+        mut bullets: Query<(
+            &Flight,
+            &mut RigidBodyVelocity,
+        )>,
+    ) {
+        let delta = clock.delta_seconds();
+        for (flight, mut vel) in bullets.iter_mut() {
+            if flight.acceleration == 0.0
+                && flight.drag == 0.0
+            {
+                continue;
+            }
+            let linvel: Vec2 = vel.linvel.into();
+            let new_speed = Flight::speed_after(
+                linvel.length(),
+                flight.acceleration,
+                flight.drag,
+                delta,
+            );
+            vel.linvel = (linvel.normalize_or_zero()
+                * new_speed)
+                .into();
+        }
+    }
+}
+
+// the bullet goes through enemies instead of breaking
+// on them until it's been through enough of them
+pub struct Pierce {
+    // how many more enemies it can go through
+    pub left: u32,
+    // the enemies it's already been through so
+    // that they don't count twice
+    pub hit: Vec<Entity>,
+}
+
+// the bullet breaks into smaller bullets when
+// it breaks on an enemy
+pub struct Split {
+    pub fragments: u32,
+}
+
+impl Split {
+    // how wide the fragments are fanned out
+    pub const SPREAD: f32 = PI * 0.5;
+}
+
+// the bullet turns towards the nearest enemy
+pub struct Homing {
+    // how fast it turns in radians a second
+    turn_rate: f32,
+}
+
+impl Homing {
+    // turns every homing bullet a bit more towards
+    // whichever enemy is closest to it
+    pub fn steer(
+        clock: Res<clock::Clock>,
+        mut bullets: Query<(
+            &Homing,
+            &RigidBodyPosition,
+            &mut RigidBodyVelocity,
+        )>,
+        enemies: Query<
+            &RigidBodyPosition,
+            With<enemies::Enemy>,
+        >,
+    ) {
+        let delta = clock.delta_seconds();
+        if delta <= 0.0 {
+            return;
+        }
+        for (homing, pos, mut vel) in bullets.iter_mut() {
+            let pos: Vec2 = pos.position.translation.into();
+            let linvel: Vec2 = vel.linvel.into();
+            let target = enemies
+                .iter()
+                .map(|enemy| -> Vec2 {
+                    enemy.position.translation.into()
+                })
+                .min_by(|a, b| {
+                    a.distance_squared(pos)
+                        .partial_cmp(
+                            &b.distance_squared(pos),
+                        )
+                        .unwrap_or(
+                            std::cmp::Ordering::Equal,
+                        )
+                });
+            let target = match target {
+                Some(target) => target,
+                None => continue,
+            };
+            // the angle between where it's going and
+            // where it wants to go, turning at most
+            // turn_rate this frame
+            let turn =
+                linvel.angle_between(target - pos).clamp(
+                    -homing.turn_rate * delta,
+                    homing.turn_rate * delta,
+                );
+            let (sin, cos) = turn.sin_cos();
+            vel.linvel = Vec2::new(
+                linvel.x * cos - linvel.y * sin,
+                linvel.x * sin + linvel.y * cos,
+            )
+            .into();
+            // spinning the bullet to match so it
+            // points where it's going
+            vel.angvel = turn / delta;
+        }
+    }
+}
+
+// the bullet blows up when its lifetime runs out
+// hurting every enemy nearby
+pub struct Explode {
+    radius: f32,
+}
+
+impl Explode {
+    // how long the blast stays on screen
+    const BLAST_TIME: f32 = 0.15;
+
+    // hurts every enemy within the blast, returning
+    // how many were actually hurt
+    pub fn blast(
+        &self,
+        commands: &mut Commands,
+        pos: Vec2,
+        damage: &health::Damage,
+        material: Handle<ColorMaterial>,
+        enemies: &mut Query<
+            (&RigidBodyPosition, &mut health::Health),
+            With<enemies::Enemy>,
+        >,
+    ) -> usize {
+        commands
+            .spawn_bundle(SpriteBundle {
+                material,
+                transform: Transform::from_translation(
+                    pos.extend(5.0),
+                ),
+                sprite: Sprite::new(Vec2::splat(
+                    self.radius * 2.0,
+                )),
+                ..Default::default()
+            })
+            .insert(Blast)
+            .insert(cooldown::Cooldown::new(Some(
+                Self::BLAST_TIME,
+            )));
+        let mut hit = 0;
+        for (enemy, mut health) in enemies.iter_mut() {
+            let enemy: Vec2 =
+                enemy.position.translation.into();
+            if enemy.distance(pos) <= self.radius
+                && health.hit(damage)
+            {
+                hit += 1;
+            }
+        }
+        hit
+    }
+}
+
+// what's left of an explosion on screen
+pub struct Blast;
+
+impl Blast {
+    // gets rid of explosions once they've been seen
+    pub fn fade(
+        mut commands: Commands,
+        blasts: Query<
+            (Entity, &cooldown::Cooldown),
+            With<Blast>,
+        >,
+    ) {
+        for (blast, lifetime) in blasts.iter() {
+            if lifetime.is_over() {
+                commands.entity(blast).despawn();
+            }
+        }
+    }
+}
 
 // holds all the components
 // that make up a bullet
@@ -14,6 +285,7 @@ pub struct Bullet;
 pub struct BulletBundle {
     // how long the bullet exists for
     lifetime: cooldown::Cooldown,
+    flight: Flight,
     just_spawned: just_spawned::JustSpawned,
     bullet: Bullet,
     sync: ColliderPositionSync,
@@ -34,12 +306,22 @@ impl BulletBundle {
         pos: Vec2,
     ) -> Self {
         BulletBundle {
-            bullet: Bullet,
+            bullet: Bullet {
+                damage: health::Damage {
+                    team: health::Team::Player,
+                    amount: builder.damage,
+                },
+                shot: 0,
+            },
             just_spawned: just_spawned::JustSpawned,
             sync: ColliderPositionSync::Discrete,
             lifetime: cooldown::Cooldown::new(Some(
                 builder.lifetime,
             )),
+            flight: Flight {
+                acceleration: builder.acceleration,
+                drag: builder.drag,
+            },
             sprite: SpriteBundle {
                 material: builder.material.clone(),
                 transform: Transform::from_translation(
@@ -59,8 +341,10 @@ impl BulletBundle {
                     // calculate the velocty the projectile
                     // should move at
                     linvel: Vec2::new(
-                        500. * (angle + 0.5 * PI).cos(),
-                        500. * (angle + 0.5 * PI).sin(),
+                        builder.speed
+                            * (angle + 0.5 * PI).cos(),
+                        builder.speed
+                            * (angle + 0.5 * PI).sin(),
                     )
                     .into(),
                     angvel: 0.0,
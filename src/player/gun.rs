@@ -1,9 +1,13 @@
 use crate::{
-    asset, cooldown, grid, item, player::bullet, state,
+    asset, clock, cooldown, enemies, grid, health, item,
+    player::{self, bullet},
+    seed, state,
 };
 use bevy::prelude::{DespawnRecursiveExt, *};
 use bevy_rapier2d::prelude::*;
 use core::f32::consts::PI;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
 
 pub struct GunPlugin;
 
@@ -16,75 +20,97 @@ impl Plugin for GunPlugin {
                 .with_system(Gun::bullet_lifetime.system())
                 .with_system(
                     Gun::bullet_collisions.system(),
-                ),
+                )
+                .with_system(
+                    bullet::Flight::update.system(),
+                )
+                .with_system(bullet::Homing::steer.system())
+                .with_system(bullet::Blast::fade.system()),
         )
         // cleaning up all the bullets at the end of the level
         .add_system(state::GameState::despawn::<
             bullet::Bullet,
-        >(
-            state::GameState::InLevel
-        ));
+        >(state::GameState::InLevel))
+        .add_system(state::GameState::despawn::<
+            bullet::Blast,
+        >(state::GameState::InLevel))
+        .init_resource::<AimRng>()
+        .add_system_set(
+            SystemSet::on_enter(state::GameState::InLevel)
+                .with_system(AimRng::reset.system()),
+        );
+    }
+}
+
+// where the gun gets the random numbers for how far
+// off each bullet goes from, taken from the run's
+// seed so a level always plays out the same way
+pub struct AimRng(seed::GameRng);
+
+impl Default for AimRng {
+    // replaced as soon as a level starts
+    fn default() -> Self {
+        Self(seed::GameRng::seed_from_u64(0))
+    }
+}
+
+impl AimRng {
+    // restarting a level starts the aim's
+    // numbers over from the beginning too
+    fn reset(
+        mut aim_rng: ResMut<AimRng>,
+        run_seed: Res<seed::RunSeed>,
+        difficulty: Res<grid::Difficulty>,
+    ) {
+        *aim_rng =
+            AimRng(run_seed.rng(
+                difficulty.level(),
+                seed::RngStream::Aim,
+            ));
     }
 }
+
 // stores all data important to
 // the gun and it shooting
 pub struct Gun {
     dir_rad: f32,
     bullets: Vec<Entity>,
+    // how many shots are left in the current burst
+    burst_left: u32,
+    // the time between each shot in a burst
+    burst_timer: Timer,
+    // how many shots have been fired, used to
+    // tell each shot's bullets apart
+    shots: u32,
+    // the shots that have already counted as a hit
+    landed: HashSet<u32>,
 }
 
 impl Gun {
-    // points the gun towards the mouse cursor
+    // points the gun towards where the player is aiming
     // both onscreen and in game logic
     pub fn point(
-        windows: Res<Windows>,
-        mut queries: QuerySet<(
-            Query<&Transform, With<crate::MainCamera>>,
-            Query<(
-                &mut Transform,
-                &GlobalTransform,
-                &mut Gun,
-            )>,
+        input: Res<player::input::PlayerInput>,
+        mut query: Query<(
+            &mut Transform,
+            &GlobalTransform,
+            &mut Gun,
         )>,
     ) {
-        // get the primary window
-        let window = windows.get_primary().unwrap();
-        // get the size of the window
-        let size = Vec2::new(
-            window.width() as f32,
-            window.height() as f32,
-        );
-        // get the position of the cursor
-        let mouse_pos = match window.cursor_position() {
-            Some(pos) => pos,
-            // if it has no position
+        let target = match input.aim {
+            Some(target) => target,
+            // if there's nothing to aim at
             // then return early
             // and leave it pointing the
-            // way it was as the cursor is
-            // off the window
+            // way it was
             None => return,
         };
 
-        // the default orthographic projection is in pixels from the center
-        // so translate it from px from top right to from center
-        let screen_pos = mouse_pos - size / 2.0;
-
-        // getting the position of the camera
-        let camera_transform =
-            queries.q0().single().unwrap();
-
-        // transforms the screen_pos into a world pos with
-        let pos_wld = camera_transform.compute_matrix()
-            * screen_pos.extend(0.0).extend(1.0);
-
-        // write our mouses xy into the game
-        let target: Vec2 = pos_wld.truncate().truncate();
-
         let (
             mut gun_transform,
             global_gun_transform,
             mut gun,
-        ) = queries.q1_mut().single_mut().unwrap();
+        ) = query.single_mut().unwrap();
 
         let gun_pos: Vec2 =
             global_gun_transform.translation.truncate();
@@ -104,93 +130,294 @@ impl Gun {
     }
 
     // detects when the player is shooting and spawns
-    // a projectile
+    // projectiles
     pub fn shoot(
         mut commands: Commands,
-        mouse_buttons: Res<Input<MouseButton>>,
+        clock: Res<clock::Clock>,
+        input: Res<player::input::PlayerInput>,
         mut gun_query: Query<(
             &GlobalTransform,
             &mut cooldown::Cooldown,
             &mut Gun,
         )>,
         config: Res<item::Config>,
+        mut aim_rng: ResMut<AimRng>,
+        mut game_events: EventWriter<state::GameEvent>,
     ) {
-        // mouse has not just been pressed
+        // getting the gun's position and the cooldown on its
+        // use as well as the actual gun itself
+        let (gun_pos, mut cooldown, mut gun) =
+            gun_query.single_mut().unwrap();
+        let pos = gun_pos.translation.truncate();
+        // the rest of a burst fires on its own
+        // whether the trigger is held or not
+        if gun.burst_left > 0 {
+            gun.burst_timer.tick(clock.delta());
+            if gun.burst_timer.finished() {
+                gun.burst_left -= 1;
+                gun.burst_timer.reset();
+                gun.fire(
+                    &mut commands,
+                    &config,
+                    &mut aim_rng.0,
+                    pos,
+                );
+                game_events
+                    .send(state::GameEvent::ShotFired);
+            }
+            return;
+        }
+        // trigger has not just been pressed
         // so the player is not trying to shoot
         // so early return
-        if config.flags.contains(&item::ItemId::AutoFire) {
-            if !mouse_buttons.pressed(MouseButton::Left) {
+        if config.flags.contains(item::Flag::AutoFire) {
+            if !input.fire {
                 return;
             }
-        } else if !mouse_buttons
-            .just_pressed(MouseButton::Left)
-        {
+        } else if !input.fire_just_pressed {
             return;
         }
-        // getting the gun's position and the cooldown on its
-        // use as well as the actual gun itself
-        let (gun_pos, mut cooldown, mut gun) =
-            gun_query.single_mut().unwrap();
         if cooldown.is_over() {
-            let angle = gun.dir_rad
-                + (rand::random::<f32>() - 0.5)
+            gun.fire(
+                &mut commands,
+                &config,
+                &mut aim_rng.0,
+                pos,
+            );
+            // restarts cooldown
+            cooldown.reset();
+            game_events.send(state::GameEvent::ShotFired);
+            // the rest of the shots in the burst
+            gun.burst_left = config.gun.burst - 1;
+            gun.burst_timer.reset();
+        }
+    }
+
+    // spawns every bullet in a shot from pos going
+    // roughly the way the gun is pointing, fanned out
+    // by the spread and side by side by the gap
+    fn fire(
+        &mut self,
+        commands: &mut Commands,
+        config: &item::Config,
+        rng: &mut seed::GameRng,
+        pos: Vec2,
+    ) {
+        let gun = &config.gun;
+        // every bullet in this shot is marked with it
+        let shot = self.shots;
+        self.shots += 1;
+        // sideways from where the gun's pointing
+        let side = Vec2::new(
+            self.dir_rad.cos(),
+            self.dir_rad.sin(),
+        );
+        for i in 0..gun.projectiles {
+            // how far from the middle of the shot this
+            // bullet is from -0.5 to 0.5
+            let offset = if gun.projectiles > 1 {
+                i as f32 / (gun.projectiles - 1) as f32
+                    - 0.5
+            } else {
+                0.0
+            };
+            let angle = self.dir_rad
+                + offset * gun.spread
+                + (rng.gen::<f32>() - 0.5)
                     * 2.0
-                    * config.gun.deviation;
+                    * gun.deviation;
+            let offset = side
+                * offset
+                * gun.barrel_gap
+                * (gun.projectiles - 1) as f32;
             // spawns a bullet and grabs it's id
-            let id = commands
-                .spawn_bundle(bullet::BulletBundle::new(
-                    config.gun.clone(),
+            let id = bullet::Bullet::spawn(
+                commands,
+                gun,
+                shot,
+                angle,
+                pos + offset,
+                Vec::new(),
+            );
+            // stores that bullets id
+            self.bullets.push(id);
+        }
+    }
+
+    // counts a shot as having hit, returning false if
+    // one of its bullets has already hit something
+    fn land(&mut self, shot: u32) -> bool {
+        self.landed.insert(shot)
+    }
+
+    // deals with a bullet going into an enemy, hurting
+    // it once, breaking the bullet unless it can pierce
+    // the enemy and splitting it if it breaks
+    // returns whether this is the first time the
+    // bullet's shot has hurt anything
+    #[allow(clippy::too_many_arguments)]
+    fn hit(
+        &mut self,
+        commands: &mut Commands,
+        bullet: Entity,
+        enemy: Entity,
+        bullets: &mut Query<(
+            &RigidBodyPosition,
+            &RigidBodyVelocity,
+            &bullet::Bullet,
+            Option<&mut bullet::Pierce>,
+            Option<&bullet::Split>,
+        )>,
+        enemies: &mut Query<
+            &mut health::Health,
+            With<enemies::Enemy>,
+        >,
+        config: &item::Config,
+    ) -> bool {
+        let (pos, vel, damage, pierce, split) =
+            match bullets.get_mut(bullet) {
+                Ok(bullet) => bullet,
+                Err(_) => return false,
+            };
+        // it's already gone through this one
+        if matches!(
+            pierce,
+            Some(ref pierce) if pierce.hit.contains(&enemy)
+        ) {
+            return false;
+        }
+        let hurt = match enemies.get_mut(enemy) {
+            Ok(mut health) => health.hit(&damage.damage),
+            Err(_) => false,
+        };
+        let shot = damage.shot;
+        let hurt = hurt && self.land(shot);
+        let hit = match pierce {
+            Some(mut pierce) => {
+                pierce.hit.push(enemy);
+                if pierce.left > 0 {
+                    pierce.left -= 1;
+                    return hurt;
+                }
+                pierce.hit.clone()
+            }
+            None => vec![enemy],
+        };
+        // the bullet's used up so it breaks into fragments
+        // fanned out around the way it was going
+        if let Some(split) = split {
+            let pos: Vec2 = pos.position.translation.into();
+            let linvel: Vec2 = vel.linvel.into();
+            let dir = linvel.y.atan2(linvel.x) - 0.5 * PI;
+            let fragment = config.gun.fragment();
+            for i in 0..split.fragments {
+                let angle = dir
+                    - bullet::Split::SPREAD / 2.0
+                    + bullet::Split::SPREAD
+                        * (i as f32 + 0.5)
+                        / split.fragments as f32;
+                // the fragments start inside the enemy
+                // so they have to ignore it
+                let id = bullet::Bullet::spawn(
+                    commands,
+                    &fragment,
+                    shot,
                     angle,
-                    gun_pos.translation.truncate(),
-                ))
-                .id();
-            // stores that bullets id and restarts cooldown
-            gun.bullets.push(id);
-            cooldown.reset();
+                    pos,
+                    hit.clone(),
+                );
+                self.bullets.push(id);
+            }
+        }
+        if let Some(idx) =
+            self.bullets.iter().position(|i| *i == bullet)
+        {
+            self.bullets.swap_remove(idx);
         }
+        commands.entity(bullet).despawn_recursive();
+        hurt
     }
 
     // despawns bullets after their cooldown is over
+    // setting off any that explode
     pub fn bullet_lifetime(
         mut commands: Commands,
-        lifetimes: Query<&cooldown::Cooldown>,
+        lifetimes: Query<(
+            &cooldown::Cooldown,
+            &RigidBodyPosition,
+            &bullet::Bullet,
+            Option<&bullet::Explode>,
+        )>,
+        mut enemies: Query<
+            (&RigidBodyPosition, &mut health::Health),
+            With<enemies::Enemy>,
+        >,
         mut gun: Query<&mut Gun>,
+        config: Res<item::Config>,
+        mut game_events: EventWriter<state::GameEvent>,
     ) {
+        let mut gun = gun.single_mut().unwrap();
+        let Gun {
+            bullets, landed, ..
+        } = &mut *gun;
         // deletes all elemets of the list
         // that don't return true for
         // the given predicate
-        gun.single_mut().unwrap().bullets.retain(
-            |&bullet| {
-                let lifetime = match lifetimes.get(bullet) {
+        bullets.retain(|&bullet| {
+            let (lifetime, pos, fired, explode) =
+                match lifetimes.get(bullet) {
                     Ok(c) => c,
                     Err(_) => return true,
                 };
-                if lifetime.is_over() {
-                    // the bullets lifetime is over
-                    // so despawn it
-                    commands
-                        .entity(bullet)
-                        .despawn_recursive();
-                    false
-                } else {
-                    true
+            if lifetime.is_over() {
+                if let Some(explode) = explode {
+                    let hit = explode.blast(
+                        &mut commands,
+                        pos.position.translation.into(),
+                        &fired.damage,
+                        config.gun.material.clone(),
+                        &mut enemies,
+                    );
+                    // the whole shot counts
+                    // as one hit at most
+                    if hit > 0 && landed.insert(fired.shot)
+                    {
+                        game_events.send(
+                            state::GameEvent::EnemyHit,
+                        );
+                    }
                 }
-            },
-        );
+                // the bullets lifetime is over
+                // so despawn it
+                commands.entity(bullet).despawn_recursive();
+                false
+            } else {
+                true
+            }
+        });
     }
 
     // handles the bullets colliding with things
+    #[allow(clippy::too_many_arguments)]
     pub fn bullet_collisions(
         mut commands: Commands,
         walls: Query<Entity, With<grid::Tile>>,
+        mut enemies: Query<
+            &mut health::Health,
+            With<enemies::Enemy>,
+        >,
+        mut bullets: Query<(
+            &RigidBodyPosition,
+            &RigidBodyVelocity,
+            &bullet::Bullet,
+            Option<&mut bullet::Pierce>,
+            Option<&bullet::Split>,
+        )>,
         mut gun: Query<&mut Gun>,
         mut contact_events: EventReader<ContactEvent>,
         config: Res<item::Config>,
+        mut game_events: EventWriter<state::GameEvent>,
     ) {
-        // if the bullets are bouncy just don't do collisions
-        if config.flags.contains(&item::ItemId::Bouncy) {
-            return;
-        }
         // getting the gun struct
         let mut gun = gun.single_mut().unwrap();
         // iterating over all the contanct events
@@ -201,29 +428,50 @@ impl Gun {
                 // the colliding entities
                 let (e1, e2) = (h1.entity(), h2.entity());
                 // no garunteed ordering of colliders
-                // so checking if either is a
-                // wall / bullet
-                for (bullet, wall) in
-                    [(e1, e2), (e2, e1)].iter()
+                // so checking both ways round
+                for (bullet, other) in [(e1, e2), (e2, e1)]
                 {
-                    // checks that the wall is a wall
-                    // and that the bullet is one
+                    // checks that the bullet is one
                     // owned by gun
-                    if let (Ok(_), Some(idx)) = (
-                        walls.get(*wall),
-                        gun.bullets
-                            .iter()
-                            .position(|i| i == bullet),
-                    ) {
-                        // for now if a bullet hits a wall
-                        // it disappears
-                        commands
-                            .entity(
-                                gun.bullets
-                                    .swap_remove(idx),
-                            )
-                            .despawn_recursive();
+                    let idx = match gun
+                        .bullets
+                        .iter()
+                        .position(|i| *i == bullet)
+                    {
+                        Some(idx) => idx,
+                        None => continue,
+                    };
+                    if walls.get(other).is_ok() {
+                        // if the bullets are bouncy they
+                        // bounce off walls, otherwise
+                        // they disappear
+                        if !config
+                            .flags
+                            .contains(item::Flag::Bouncy)
+                        {
+                            commands
+                                .entity(
+                                    gun.bullets
+                                        .swap_remove(idx),
+                                )
+                                .despawn_recursive();
+                        }
+                    } else if enemies.get_mut(other).is_ok()
+                        && gun.hit(
+                            &mut commands,
+                            bullet,
+                            other,
+                            &mut bullets,
+                            &mut enemies,
+                            &config,
+                        )
+                    {
+                        // only counting hits that hurt
+                        game_events.send(
+                            state::GameEvent::EnemyHit,
+                        );
                     }
+                    break;
                 }
             }
         }
@@ -250,6 +498,13 @@ impl GunBundle {
             gun: Gun {
                 dir_rad: 0.0,
                 bullets: Vec::new(),
+                burst_left: 0,
+                burst_timer: Timer::from_seconds(
+                    builder.burst_interval,
+                    false,
+                ),
+                shots: 0,
+                landed: HashSet::new(),
             },
             cooldown,
             sprite: SpriteBundle {
@@ -274,15 +529,44 @@ pub struct GunBuilder {
     pub material: Handle<ColorMaterial>,
     pub deviation: f32,
     pub lifetime: f32,
+    // how fast bullets start off
     pub speed: f32,
+    // how much speed bullets gain a second
+    // (negative to slow down)
+    pub acceleration: f32,
+    // the fraction of their speed bullets lose a second
+    pub drag: f32,
+    // how much each bullet hurts an enemy
+    pub damage: f32,
+    // how many enemies each bullet goes through
+    // before it breaks
+    pub pierce: u32,
+    // how many fragments a bullet breaks into
+    // when it breaks on an enemy
+    pub split: u32,
+    // how fast bullets turn towards the nearest
+    // enemy in radians a second
+    pub homing: f32,
+    // how far the blast reaches when a bullet's
+    // lifetime runs out (none if zero)
+    pub explosion: f32,
+    // how many bullets are fired at once
+    pub projectiles: u32,
+    // the angle in radians between the outermost
+    // bullets fired at once
+    pub spread: f32,
+    // how far apart bullets fired at once start
+    pub barrel_gap: f32,
+    // how many shots are fired one after another
+    // each time the trigger is pulled
+    pub burst: u32,
+    // how many seconds apart the shots in a burst are
+    pub burst_interval: f32,
 }
 
-impl FromWorld for GunBuilder {
+impl GunBuilder {
     // returns a default version of the GunBuilder
-    fn from_world(world: &mut World) -> Self {
-        let materials = world
-            .get_resource::<asset::Materials>()
-            .unwrap();
+    pub fn new(materials: &asset::Materials) -> Self {
         Self {
             size: Vec2::new(5.0, 10.0),
             cooldown: 0.3,
@@ -290,6 +574,72 @@ impl FromWorld for GunBuilder {
             deviation: 0.1,
             lifetime: 1.0,
             speed: 500.,
+            acceleration: 0.0,
+            drag: 0.0,
+            damage: 1.0,
+            pierce: 0,
+            split: 0,
+            homing: 0.0,
+            explosion: 0.0,
+            projectiles: 1,
+            spread: 0.0,
+            barrel_gap: 0.0,
+            burst: 1,
+            burst_interval: 0.06,
+        }
+    }
+
+    // how far a bullet goes before it runs out, worked
+    // out a frame at a time the same way it flies so
+    // that speeding up and slowing down count
+    pub fn range(&self) -> f32 {
+        // nothing changes the speed so there's no
+        // need to go a frame at a time
+        if self.acceleration == 0.0 && self.drag == 0.0 {
+            return self.speed * self.lifetime;
+        }
+        // a frame at a time unless the bullet lives so
+        // long that would take too many steps to be
+        // worked out whenever the item menu shows
+        const MAX_STEPS: f32 = 600.0;
+        let step =
+            (1.0 / 60.0_f32).max(self.lifetime / MAX_STEPS);
+        let mut speed = self.speed;
+        let mut range = 0.0;
+        let mut left = self.lifetime;
+        while left > 0.0 {
+            let delta = left.min(step);
+            speed = bullet::Flight::speed_after(
+                speed,
+                self.acceleration,
+                self.drag,
+                delta,
+            );
+            range += speed * delta;
+            left -= delta;
         }
+        range
+    }
+
+    // what the bullets that a bullet splits into are like
+    pub fn fragment(&self) -> Self {
+        Self {
+            size: self.size * 0.6,
+            damage: self.damage * 0.5,
+            lifetime: self.lifetime * 0.5,
+            pierce: 0,
+            split: 0,
+            ..self.clone()
+        }
+    }
+}
+
+impl FromWorld for GunBuilder {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(
+            world
+                .get_resource::<asset::Materials>()
+                .unwrap(),
+        )
     }
 }
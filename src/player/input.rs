@@ -0,0 +1,284 @@
+use crate::{
+    actions::{Action, ActionSystem, Actions},
+    player, MainCamera,
+};
+use bevy::{input::InputSystem, prelude::*};
+
+// reads the keyboard and mouse or a gamepad into PlayerInput
+// only added when there is a window to read from
+pub struct DeviceInputPlugin;
+
+impl Plugin for DeviceInputPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<InputDevice>()
+            .add_system_to_stage(
+                // running before any of the game logic
+                // but after bevy has read the devices
+                CoreStage::PreUpdate,
+                PlayerInput::from_devices
+                    .system()
+                    .after(InputSystem)
+                    .after(ActionSystem),
+            );
+    }
+}
+
+// holds what the player is trying to do this frame
+// the player and gun only ever read this so it can
+// be written by a keyboard, a script or anything else
+#[derive(Debug, Clone, Default)]
+pub struct PlayerInput {
+    // the direction the player wants to move in
+    pub movement: Vec2,
+    // the point in the world the player is aiming at
+    pub aim: Option<Vec2>,
+    // whether the trigger is held down
+    pub fire: bool,
+    // whether the trigger was pressed this frame
+    pub fire_just_pressed: bool,
+    // whether the player wants to dash this frame
+    pub dash: bool,
+}
+
+// which device the player is currently using, whichever
+// was touched last wins so they can be swapped at any time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputDevice {
+    KeyboardMouse,
+    Gamepad(Gamepad),
+}
+
+impl Default for InputDevice {
+    fn default() -> Self {
+        InputDevice::KeyboardMouse
+    }
+}
+
+impl InputDevice {
+    // how far a stick has to be pushed before
+    // it counts as the gamepad being used
+    const DEADZONE: f32 = 0.3;
+
+    // ignores a stick that's resting inside the deadzone
+    // (so drift doesn't move anything) and scales the
+    // rest so it still goes smoothly from zero to one
+    fn deadzone(stick: Vec2) -> Vec2 {
+        let length = stick.length();
+        if length <= Self::DEADZONE {
+            return Vec2::ZERO;
+        }
+        let scaled = ((length - Self::DEADZONE)
+            / (1.0 - Self::DEADZONE))
+            .min(1.0);
+        stick / length * scaled
+    }
+
+    // switches device whenever a different one is used
+    fn detect(
+        &mut self,
+        keys: &Input<KeyCode>,
+        mouse_buttons: &Input<MouseButton>,
+        cursor_moved: &mut EventReader<CursorMoved>,
+        gamepad_buttons: &Input<GamepadButton>,
+        gamepad_events: &mut EventReader<GamepadEvent>,
+    ) {
+        if keys.get_just_pressed().next().is_some()
+            || mouse_buttons
+                .get_just_pressed()
+                .next()
+                .is_some()
+            || cursor_moved.iter().next().is_some()
+        {
+            *self = InputDevice::KeyboardMouse;
+        }
+        if let Some(GamepadButton(gamepad, _)) =
+            gamepad_buttons.get_just_pressed().next()
+        {
+            *self = InputDevice::Gamepad(*gamepad);
+        }
+        for GamepadEvent(gamepad, event) in
+            gamepad_events.iter()
+        {
+            match event {
+                GamepadEventType::AxisChanged(_, value)
+                    if value.abs() > Self::DEADZONE =>
+                {
+                    *self = InputDevice::Gamepad(*gamepad);
+                }
+                // going back to the keyboard if the
+                // gamepad being used is unplugged
+                GamepadEventType::Disconnected
+                    if *self
+                        == InputDevice::Gamepad(
+                            *gamepad,
+                        ) =>
+                {
+                    *self = InputDevice::KeyboardMouse;
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl PlayerInput {
+    // how far from the player the aim point is put
+    // when aiming with a stick
+    const STICK_AIM_DISTANCE: f32 = 100.0;
+
+    // fills in the input from whichever
+    // device the player is using
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_devices(
+        mut input: ResMut<PlayerInput>,
+        mut device: ResMut<InputDevice>,
+        actions: Res<Actions>,
+        keys: Res<Input<KeyCode>>,
+        mouse_buttons: Res<Input<MouseButton>>,
+        mut cursor_moved: EventReader<CursorMoved>,
+        gamepad_buttons: Res<Input<GamepadButton>>,
+        gamepad_axes: Res<Axis<GamepadAxis>>,
+        mut gamepad_events: EventReader<GamepadEvent>,
+        windows: Res<Windows>,
+        camera: Query<&Transform, With<MainCamera>>,
+        player: Query<&Transform, With<player::Player>>,
+    ) {
+        device.detect(
+            &keys,
+            &mouse_buttons,
+            &mut cursor_moved,
+            &gamepad_buttons,
+            &mut gamepad_events,
+        );
+        match *device {
+            InputDevice::KeyboardMouse => input
+                .read_keyboard_mouse(
+                    &actions, &windows, &camera,
+                ),
+            InputDevice::Gamepad(gamepad) => input
+                .read_gamepad(
+                    gamepad,
+                    &actions,
+                    &gamepad_axes,
+                    &player,
+                ),
+        }
+    }
+
+    // twin stick controls, left stick moves, right
+    // stick aims and fire is whatever it's bound to
+    // (the right trigger by default)
+    fn read_gamepad(
+        &mut self,
+        gamepad: Gamepad,
+        actions: &Actions,
+        axes: &Axis<GamepadAxis>,
+        player: &Query<&Transform, With<player::Player>>,
+    ) {
+        let stick = |x, y| {
+            Vec2::new(
+                axes.get(GamepadAxis(gamepad, x))
+                    .unwrap_or(0.0),
+                axes.get(GamepadAxis(gamepad, y))
+                    .unwrap_or(0.0),
+            )
+        };
+        // keeping how far the stick is pushed
+        // so the player can move slowly
+        self.movement = InputDevice::deadzone(stick(
+            GamepadAxisType::LeftStickX,
+            GamepadAxisType::LeftStickY,
+        ));
+        let aim = stick(
+            GamepadAxisType::RightStickX,
+            GamepadAxisType::RightStickY,
+        );
+        // a resting stick keeps the gun pointing
+        // wherever it was last pointed
+        self.aim = match player.single() {
+            Ok(transform)
+                if aim.length() > InputDevice::DEADZONE =>
+            {
+                Some(
+                    transform.translation.truncate()
+                        + aim.normalize()
+                            * Self::STICK_AIM_DISTANCE,
+                )
+            }
+            _ => None,
+        };
+        self.fire = actions.pressed(Action::Fire);
+        self.fire_just_pressed =
+            actions.just_pressed(Action::Fire);
+        self.dash = actions.just_pressed(Action::Dash);
+    }
+
+    // the mouse aims and the rest is
+    // whatever the actions are bound to
+    fn read_keyboard_mouse(
+        &mut self,
+        actions: &Actions,
+        windows: &Windows,
+        camera: &Query<&Transform, With<MainCamera>>,
+    ) {
+        let mut dir = Vec2::ZERO;
+        // move up
+        if actions.pressed(Action::MoveUp) {
+            dir.y += 1.0
+        }
+        // move left
+        if actions.pressed(Action::MoveLeft) {
+            dir.x -= 1.0
+        }
+        // move down
+        if actions.pressed(Action::MoveDown) {
+            dir.y -= 1.0
+        }
+        // move right
+        if actions.pressed(Action::MoveRight) {
+            dir.x += 1.0
+        }
+        self.movement = dir;
+
+        self.fire = actions.pressed(Action::Fire);
+        self.fire_just_pressed =
+            actions.just_pressed(Action::Fire);
+        self.dash = actions.just_pressed(Action::Dash);
+
+        // get the primary window
+        let window = windows.get_primary().unwrap();
+        // get the size of the window
+        let size = Vec2::new(
+            window.width() as f32,
+            window.height() as f32,
+        );
+        // get the position of the cursor
+        let mouse_pos = match window.cursor_position() {
+            Some(pos) => pos,
+            // if it has no position then the cursor
+            // is off the window so there's nothing
+            // to aim at
+            None => {
+                self.aim = None;
+                return;
+            }
+        };
+
+        // the default orthographic projection is in pixels from the center
+        // so translate it from px from top right to from center
+        let screen_pos = mouse_pos - size / 2.0;
+
+        // getting the position of the camera
+        let camera_transform = match camera.single() {
+            Ok(transform) => transform,
+            Err(_) => return,
+        };
+
+        // transforms the screen_pos into a world pos with
+        let pos_wld = camera_transform.compute_matrix()
+            * screen_pos.extend(0.0).extend(1.0);
+
+        // write our mouses xy into the game
+        self.aim = Some(pos_wld.truncate().truncate());
+    }
+}
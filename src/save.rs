@@ -0,0 +1,182 @@
+use crate::{
+    grid, item, menus, player, seed, state, stats, storage,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            // the item menu is only ever reached by clearing
+            // a level so that's when the run gets saved
+            SystemSet::on_enter(state::GameState::ItemMenu)
+                .with_system(RunSave::save.system()),
+        )
+        // rerolling in the item menu costs lives and
+        // changes the offer so the save has to keep up
+        // (as do the stats if the level's end is only
+        // counted after the menu's opened)
+        // (skipping's extra life isn't saved until the
+        // next level's item menu or it could be taken
+        // again and again by continuing)
+        .add_system_set(
+            SystemSet::on_update(
+                state::GameState::ItemMenu,
+            )
+            .with_system(RunSave::resave.system()),
+        )
+        .add_system(RunSave::delete.system());
+    }
+}
+
+// everything needed to pick a run back up
+// from the start of the next level's item menu
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunSave {
+    // saves from a different version might not
+    // mean the same thing so they're ignored
+    version: u32,
+    seed: u64,
+    level: u32,
+    lives: u32,
+    items: Vec<item::ItemId>,
+    rerolls: u32,
+    // the run's stats so far so a continued run
+    // is ranked on the whole run
+    kills: u32,
+    shots: u32,
+    hits: u32,
+    level_times: Vec<f32>,
+}
+
+impl RunSave {
+    // bump this whenever the layout of the save changes
+    const VERSION: u32 = 4;
+    // the name the save is stored under
+    const KEY: &'static str = "run";
+
+    // loads the saved run if there is one
+    // that this version can read and that could
+    // actually have been played
+    pub fn load() -> Option<RunSave> {
+        let save: RunSave =
+            ron::from_str(&storage::read(Self::KEY)?)
+                .ok()?;
+        if save.version == Self::VERSION
+            && save.level >= 1
+            && save.lives >= 1
+        {
+            Some(save)
+        } else {
+            None
+        }
+    }
+
+    // puts the saved run back into the game
+    // so it can be continued
+    pub fn restore(self, commands: &mut Commands) {
+        commands
+            .insert_resource(seed::RunSeed::new(self.seed));
+        commands.insert_resource(
+            grid::Difficulty::at_level(self.level),
+        );
+        commands.insert_resource(player::Lives(self.lives));
+        commands.insert_resource(stats::RunStats {
+            kills: self.kills,
+            shots: self.shots,
+            hits: self.hits,
+            level_times: self.level_times,
+            current_time: 0.0,
+            items: self.items.clone(),
+        });
+        commands.insert_resource(
+            item::ItemManager::from_ids(self.items),
+        );
+        commands.insert_resource(
+            menus::ItemOffer::rerolled(self.rerolls),
+        );
+    }
+
+    // writes the current run to storage
+    fn save(
+        run_seed: Res<seed::RunSeed>,
+        difficulty: Res<grid::Difficulty>,
+        lives: Res<player::Lives>,
+        items: Res<item::ItemManager>,
+        offer: Res<menus::ItemOffer>,
+        stats: Res<stats::RunStats>,
+    ) {
+        RunSave::write(
+            &run_seed,
+            &difficulty,
+            &lives,
+            &items,
+            &offer,
+            &stats,
+        )
+    }
+
+    // saves the run again if the offer has been
+    // rerolled or the stats have caught up
+    fn resave(
+        run_seed: Res<seed::RunSeed>,
+        difficulty: Res<grid::Difficulty>,
+        lives: Res<player::Lives>,
+        items: Res<item::ItemManager>,
+        offer: Res<menus::ItemOffer>,
+        stats: Res<stats::RunStats>,
+    ) {
+        if offer.is_changed() || stats.is_changed() {
+            RunSave::write(
+                &run_seed,
+                &difficulty,
+                &lives,
+                &items,
+                &offer,
+                &stats,
+            )
+        }
+    }
+
+    fn write(
+        run_seed: &seed::RunSeed,
+        difficulty: &grid::Difficulty,
+        lives: &player::Lives,
+        items: &item::ItemManager,
+        offer: &menus::ItemOffer,
+        stats: &stats::RunStats,
+    ) {
+        let save = RunSave {
+            version: Self::VERSION,
+            seed: run_seed.value(),
+            level: difficulty.level(),
+            lives: lives.0,
+            items: items.ids().to_vec(),
+            rerolls: offer.rerolls(),
+            kills: stats.kills,
+            shots: stats.shots,
+            hits: stats.hits,
+            level_times: stats.level_times.clone(),
+        };
+        let result = ron::to_string(&save)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                storage::write(Self::KEY, &text)
+            });
+        if let Err(e) = result {
+            error!("Couldn't save the run: {}", e);
+        }
+    }
+
+    // once the game is over there's no
+    // run left to continue
+    fn delete(mut events: EventReader<state::GameEvent>) {
+        if events.iter().any(|event| {
+            matches!(event, state::GameEvent::GameOver)
+        }) {
+            storage::remove(Self::KEY);
+        }
+    }
+}
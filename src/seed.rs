@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+pub struct SeedPlugin;
+
+impl Plugin for SeedPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // replaced with a fresh random seed whenever
+        // a new run is started from the main menu
+        app.init_resource::<RunSeed>();
+    }
+}
+
+// the rng used everywhere that needs to be
+// reproducible from the run's seed
+// (a named algorithm rather than StdRng which rand
+// can change between versions, breaking shared seeds)
+pub type GameRng = ChaCha8Rng;
+
+// the different things that need random numbers
+// each one gets its own stream so that rolling an
+// item doesn't change how the next level generates
+#[derive(Debug, Clone, Copy)]
+pub enum RngStream {
+    Generation,
+    Items,
+    Patrol,
+    Aim,
+}
+
+// the seed for the whole run, every level and item
+// offer is derived from this so sharing it means
+// sharing the exact same run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunSeed(u64);
+
+impl Default for RunSeed {
+    // picks a random seed for a new run
+    fn default() -> Self {
+        Self(rand::random())
+    }
+}
+
+impl RunSeed {
+    // creates a run seed from a known value
+    // (for replaying or sharing a run)
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    // returns the raw seed value so it can be
+    // shown to the player
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    // creates a deterministic rng for the given level
+    // and stream, so asking twice gives the same numbers
+    pub fn rng(
+        &self,
+        level: u32,
+        stream: RngStream,
+    ) -> GameRng {
+        // mixing the level and stream into the seed
+        // with a large odd constant so that neighbouring
+        // levels don't get similar looking seeds
+        let salt = (((level as u64) << 8) | stream as u64)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        GameRng::seed_from_u64(self.0 ^ salt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Difficulty, Grid};
+
+    // generates the given level from a seed the same
+    // way Grid::level_generate does
+    fn level(seed: RunSeed, level: u32) -> Grid {
+        let mut rng =
+            seed.rng(level, RngStream::Generation);
+        Grid::generate(
+            &mut rng,
+            &Difficulty::at_level(level),
+        )
+    }
+
+    #[test]
+    fn same_seed_same_grid() {
+        let seed = RunSeed::new(1234);
+        for n in 1..4 {
+            assert_eq!(level(seed, n), level(seed, n));
+        }
+    }
+
+    #[test]
+    fn different_seed_different_grid() {
+        assert_ne!(
+            level(RunSeed::new(1234), 1),
+            level(RunSeed::new(4321), 1)
+        );
+    }
+
+    #[test]
+    fn levels_differ_within_a_run() {
+        let seed = RunSeed::new(1234);
+        assert_ne!(level(seed, 1), level(seed, 2));
+    }
+}
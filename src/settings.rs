@@ -0,0 +1,213 @@
+use crate::{asset, storage};
+use bevy::{prelude::*, window::WindowMode};
+use serde::{Deserialize, Serialize};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // the settings themselves are loaded in main
+        // so the window and colors can be built from them
+        app.add_system(Settings::apply.system());
+    }
+}
+
+// how the game window is shown
+#[derive(
+    Debug, Clone, Copy, PartialEq, Serialize, Deserialize,
+)]
+pub enum WindowSetting {
+    Windowed { width: f32, height: f32 },
+    Fullscreen,
+}
+
+impl WindowSetting {
+    // every option the settings menu cycles through
+    pub const ALL: [WindowSetting; 4] = [
+        WindowSetting::Windowed {
+            width: crate::WINDOW_WIDTH,
+            height: crate::WINDOW_HEIGHT,
+        },
+        WindowSetting::Windowed {
+            width: 1024.0,
+            height: 768.0,
+        },
+        WindowSetting::Windowed {
+            width: 1280.0,
+            height: 960.0,
+        },
+        WindowSetting::Fullscreen,
+    ];
+
+    // the name shown to the player
+    pub fn name(&self) -> String {
+        match self {
+            WindowSetting::Windowed { width, height } => {
+                format!("{}x{}", width, height)
+            }
+            WindowSetting::Fullscreen => {
+                "Fullscreen".to_string()
+            }
+        }
+    }
+
+    // the size of the window before it's fullscreened
+    pub fn size(&self) -> (f32, f32) {
+        match *self {
+            WindowSetting::Windowed { width, height } => {
+                (width, height)
+            }
+            WindowSetting::Fullscreen => {
+                (crate::WINDOW_WIDTH, crate::WINDOW_HEIGHT)
+            }
+        }
+    }
+
+    // bevy's version of this setting
+    pub fn mode(&self) -> WindowMode {
+        match self {
+            WindowSetting::Windowed { .. } => {
+                WindowMode::Windowed
+            }
+            WindowSetting::Fullscreen => {
+                WindowMode::BorderlessFullscreen
+            }
+        }
+    }
+}
+
+// which set of colors the game is drawn with
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Palette {
+    Neon,
+    HighContrast,
+}
+
+impl Palette {
+    // every option the settings menu cycles through
+    pub const ALL: [Palette; 2] =
+        [Palette::Neon, Palette::HighContrast];
+
+    // the name shown to the player
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Neon => "Neon",
+            Palette::HighContrast => "High Contrast",
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::Neon
+    }
+}
+
+// all the options the player can change
+// kept between sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    // settings saved by a different version
+    // might not mean the same thing
+    version: u32,
+    pub window: WindowSetting,
+    // how many seconds the countdown
+    // before each level counts down from
+    pub countdown: f32,
+    pub palette: Palette,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            window: WindowSetting::ALL[0],
+            countdown: 3.0,
+            palette: Palette::default(),
+        }
+    }
+}
+
+impl Settings {
+    // bump this whenever the layout of the settings changes
+    const VERSION: u32 = 1;
+    // the name the settings are stored under
+    const KEY: &'static str = "settings";
+    // every countdown length the menu cycles through
+    pub const COUNTDOWNS: [f32; 5] =
+        [0.0, 1.0, 2.0, 3.0, 5.0];
+
+    // loads the saved settings or the defaults if
+    // there aren't any that this version can read
+    pub fn load() -> Settings {
+        storage::read(Self::KEY)
+            .and_then(|text| {
+                ron::from_str::<Settings>(&text).ok()
+            })
+            .filter(|settings| {
+                settings.version == Self::VERSION
+            })
+            .unwrap_or_default()
+    }
+
+    // writes the settings to storage
+    pub fn save(&self) {
+        let result = ron::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                storage::write(Self::KEY, &text)
+            });
+        if let Err(e) = result {
+            error!("Couldn't save the settings: {}", e);
+        }
+    }
+
+    // the window the game should open with
+    pub fn window_descriptor(&self) -> WindowDescriptor {
+        let (width, height) = self.window.size();
+        WindowDescriptor {
+            title: "Neon Daze".to_string(),
+            width,
+            height,
+            mode: self.window.mode(),
+            resizable: false,
+            ..Default::default()
+        }
+    }
+
+    // makes any changes to the settings take effect
+    fn apply(
+        settings: Res<Settings>,
+        mut windows: ResMut<Windows>,
+        materials: Res<asset::Materials>,
+        mut colors: ResMut<Assets<ColorMaterial>>,
+        mut clear_color: ResMut<ClearColor>,
+    ) {
+        // the window and colors are already
+        // built from the settings at startup
+        if !settings.is_changed() || settings.is_added() {
+            return;
+        }
+        if let Some(window) = windows.get_primary_mut() {
+            window.set_mode(settings.window.mode());
+            if let WindowSetting::Windowed {
+                width,
+                height,
+            } = settings.window
+            {
+                window.set_resolution(width, height);
+            }
+        }
+        materials.recolor(settings.palette, &mut colors);
+        *clear_color =
+            asset::Materials::clear_color(settings.palette);
+    }
+}
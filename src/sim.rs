@@ -0,0 +1,317 @@
+use crate::{
+    asset, clock, cooldown, enemies, grid, health, item,
+    just_spawned, phys,
+    player::{self, input::PlayerInput},
+    seed, state,
+};
+use bevy::{prelude::*, transform::TransformPlugin};
+use bevy_rapier2d::prelude::*;
+use std::time::Duration;
+
+// how many frames make up a second of the simulation
+// (matching the physics' own fixed 1/60s step)
+pub const FRAMES_PER_SEC: u32 = 60;
+
+// builds an app that runs the game's logic without
+// a window, renderer or any menus so that levels
+// can be played out by a script (e.g. in CI)
+pub fn headless_app(
+    run_seed: seed::RunSeed,
+    script: InputScript,
+) -> AppBuilder {
+    let mut app = App::build();
+    app
+        // just the engine's core (time, tasks) and
+        // nothing that needs a window or gpu
+        .add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        // the game still stores colors and fonts
+        // in handles so their stores need to exist
+        .add_plugin(bevy::asset::AssetPlugin)
+        .add_asset::<ColorMaterial>()
+        .add_asset::<Font>()
+        .init_resource::<asset::Materials>()
+        // every frame is the same length so a run
+        // always plays out the same way
+        .add_plugin(clock::ClockPlugin)
+        .insert_resource(clock::Clock::fixed(
+            Duration::from_secs(1) / FRAMES_PER_SEC,
+        ))
+        .add_plugin(cooldown::CooldownPlugin)
+        .add_plugin(state::StatePlugin)
+        .add_plugin(seed::SeedPlugin)
+        // overwriting the random seed with the given one
+        .insert_resource(run_seed)
+        .add_plugin(
+            RapierPhysicsPlugin::<NoUserData>::default(),
+        )
+        .add_startup_system(phys::disable_gravity.system())
+        .add_plugin(grid::GridPlugin)
+        .add_plugin(player::PlayerPlugin)
+        .add_plugin(enemies::EnemyPlugin)
+        .add_plugin(health::HealthPlugin)
+        .add_plugin(item::ItemPlugin)
+        .add_plugin(just_spawned::JustSpawnedPlugin)
+        // the script takes the place of the keyboard
+        // and mouse
+        .insert_resource(script)
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            InputScript::drive.system(),
+        )
+        .init_resource::<SimReport>()
+        .add_system(skip_menus.system())
+        .add_system_set(
+            SystemSet::on_enter(state::GameState::InLevel)
+                .with_system(
+                    SimReport::start_level.system(),
+                ),
+        )
+        .add_system(SimReport::record.system());
+    app
+}
+
+// runs a headless app until the run is over or it
+// has gone for the given number of frames and returns
+// what happened in each level
+pub fn run(mut app: AppBuilder, frames: u32) -> SimReport {
+    for _ in 0..frames {
+        app.app.update();
+        let report = app
+            .world()
+            .get_resource::<SimReport>()
+            .unwrap();
+        if report.game_over {
+            break;
+        }
+    }
+    app.world().get_resource::<SimReport>().unwrap().clone()
+}
+
+// there's no one to click through the menus so
+// this skips straight past them
+fn skip_menus(
+    mut game_state: ResMut<State<state::GameState>>,
+) {
+    match game_state.current() {
+        // nothing to wait for as nothing is rendered
+        state::GameState::Loading => {
+            game_state
+                .set(state::GameState::LoadingLevel)
+                .unwrap();
+        }
+        // no item is taken between levels
+        state::GameState::ItemMenu => {
+            game_state
+                .overwrite_set(
+                    state::GameState::LoadingLevel,
+                )
+                .unwrap();
+        }
+        _ => {}
+    }
+}
+
+// what a script can see when deciding what to do
+pub struct SimView<'a> {
+    pub player: Vec2,
+    pub enemies: &'a [Vec2],
+}
+
+// decides the player's input each frame in place of
+// a keyboard and mouse
+pub struct InputScript(
+    Box<dyn FnMut(&SimView) -> PlayerInput + Send + Sync>,
+);
+
+impl InputScript {
+    // creates a script from a function that's called
+    // every frame the player is alive
+    pub fn new<F>(f: F) -> Self
+    where
+        F: FnMut(&SimView) -> PlayerInput
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self(Box::new(f))
+    }
+
+    // a simple bot that stands still and shoots at
+    // the closest enemy as fast as it can
+    pub fn nearest_enemy() -> Self {
+        // flipped every frame so that guns
+        // without AutoFire get a fresh trigger press
+        let mut pressed = false;
+        Self::new(move |view| {
+            pressed = !pressed;
+            let aim = view.enemies.iter().copied().min_by(
+                |a, b| {
+                    a.distance_squared(view.player)
+                        .partial_cmp(
+                            &b.distance_squared(
+                                view.player,
+                            ),
+                        )
+                        .unwrap()
+                },
+            );
+            PlayerInput {
+                movement: Vec2::ZERO,
+                aim,
+                fire: aim.is_some(),
+                fire_just_pressed: aim.is_some() && pressed,
+                dash: false,
+            }
+        })
+    }
+
+    // fills in the player's input from the script
+    fn drive(
+        mut script: ResMut<InputScript>,
+        mut input: ResMut<PlayerInput>,
+        player: Query<&Transform, With<player::Player>>,
+        enemies: Query<&Transform, With<enemies::Enemy>>,
+    ) {
+        // no player means not in a level
+        // so there's nothing to control
+        let player = match player.single() {
+            Ok(transform) => {
+                transform.translation.truncate()
+            }
+            Err(_) => {
+                *input = PlayerInput::default();
+                return;
+            }
+        };
+        let enemies = enemies
+            .iter()
+            .map(|transform| {
+                transform.translation.truncate()
+            })
+            .collect::<Vec<_>>();
+        *input = (script.0)(&SimView {
+            player,
+            enemies: &enemies,
+        });
+    }
+}
+
+// how a level ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelResult {
+    Cleared,
+    PlayerHit,
+}
+
+// the result of one attempt at a level
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelOutcome {
+    pub level: u32,
+    pub result: LevelResult,
+    pub secs: f64,
+}
+
+// everything that happened during a simulated run
+#[derive(Debug, Clone, Default)]
+pub struct SimReport {
+    pub outcomes: Vec<LevelOutcome>,
+    pub game_over: bool,
+    // the level being played and when it started
+    current: Option<(u32, f64)>,
+}
+
+impl SimReport {
+    // notes which level has just started and when
+    fn start_level(
+        mut report: ResMut<SimReport>,
+        difficulty: Res<grid::Difficulty>,
+        clock: Res<clock::Clock>,
+    ) {
+        report.current = Some((
+            difficulty.level(),
+            clock.seconds_since_startup(),
+        ));
+    }
+
+    // records the outcome of the current level
+    // from the game's events
+    fn record(
+        mut report: ResMut<SimReport>,
+        mut events: EventReader<state::GameEvent>,
+        clock: Res<clock::Clock>,
+    ) {
+        for event in events.iter() {
+            let result = match event {
+                state::GameEvent::LevelClear => {
+                    LevelResult::Cleared
+                }
+                state::GameEvent::PlayerHit => {
+                    LevelResult::PlayerHit
+                }
+                state::GameEvent::GameOver => {
+                    report.game_over = true;
+                    continue;
+                }
+                // nothing to do with how the level ended
+                _ => continue,
+            };
+            // taking the current level so the same level
+            // can't end twice if an event is sent over
+            // multiple frames
+            if let Some((level, start)) =
+                report.current.take()
+            {
+                report.outcomes.push(LevelOutcome {
+                    level,
+                    result,
+                    secs: clock.seconds_since_startup()
+                        - start,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // plays the first minute of a run with the
+    // nearest enemy bot
+    fn play(seed: u64) -> SimReport {
+        run(
+            headless_app(
+                seed::RunSeed::new(seed),
+                InputScript::nearest_enemy(),
+            ),
+            60 * FRAMES_PER_SEC,
+        )
+    }
+
+    #[test]
+    fn same_seed_same_report() {
+        let first = play(1234);
+        let second = play(1234);
+        // something has to have happened for the
+        // comparison to mean anything
+        assert!(!first.outcomes.is_empty());
+        assert_eq!(first.outcomes, second.outcomes);
+        assert_eq!(first.game_over, second.game_over);
+    }
+
+    #[test]
+    fn outcomes_fit_in_the_frames_run() {
+        let report = play(4321);
+        let total: f64 = report
+            .outcomes
+            .iter()
+            .map(|outcome| outcome.secs)
+            .sum();
+        assert!(total <= 60.0);
+        // levels are numbered from the first one on
+        for outcome in report.outcomes.iter() {
+            assert!(outcome.level >= 1);
+        }
+    }
+}
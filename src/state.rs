@@ -31,6 +31,13 @@ pub enum GameState {
     Loading,
     LevelRestart,
     ItemMenu,
+    // showing how the run went before
+    // going back to the main menu
+    GameOver,
+    // changing the bindings
+    Controls,
+    // changing the settings
+    Settings,
 }
 // due to this type of pattern being so common Bevy
 // already has internal systems to manage state
@@ -59,6 +66,23 @@ impl GameState {
         )
     }
 
+    // despawns everything of type T when another
+    // state is pushed on top of the given one
+    pub fn despawn_on_pause<T: Component>(
+        state: GameState,
+    ) -> ParallelSystemDescriptor {
+        (|mut commmands: Commands,
+          query: Query<Entity, With<T>>| {
+            for entity in query.iter() {
+                commmands.entity(entity).despawn_recursive()
+            }
+        })
+        .system()
+        .with_run_criteria(
+            State::<GameState>::on_pause(state),
+        )
+    }
+
     // when the LevelRestart state is set just re-enter
     // the InLevel state to restart the level
     pub fn level_restart(
@@ -80,6 +104,11 @@ pub enum GameEvent {
     LevelClear,
     PlayerHit,
     GameOver,
+    // these don't change the state but are
+    // counted up for the run's stats
+    ShotFired,
+    EnemyHit,
+    EnemyKilled,
 }
 
 impl GameEvent {
@@ -90,10 +119,10 @@ impl GameEvent {
     ) {
         // collecting the events
         let events = events.iter().collect::<Vec<_>>();
-        // if the game is over then return to the main menu
+        // if the game is over then show how the run went
         if events.contains(&&GameEvent::GameOver) {
             app_state
-                .overwrite_set(GameState::MainMenu)
+                .overwrite_set(GameState::GameOver)
                 .unwrap()
         } else if events.contains(&&GameEvent::PlayerHit) {
             // if the player is hit restart the level
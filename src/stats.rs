@@ -0,0 +1,224 @@
+use crate::{clock, item, seed, state, storage};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<RunStats>()
+            .init_resource::<Leaderboard>()
+            .add_system(RunStats::record.system())
+            .add_system_set(
+                SystemSet::on_update(
+                    state::GameState::InLevel,
+                )
+                .with_system(RunStats::tick.system()),
+            )
+            .add_system_set(
+                // the items are settled once the
+                // next level starts loading
+                SystemSet::on_enter(
+                    state::GameState::LoadingLevel,
+                )
+                .with_system(RunStats::note_items.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(
+                    state::GameState::GameOver,
+                )
+                .with_system(
+                    Leaderboard::submit
+                        .system()
+                        .label(StatsSystem::Submit),
+                ),
+            )
+            .add_system_set(
+                // only forgetting the run once the
+                // summary has been looked at
+                SystemSet::on_exit(
+                    state::GameState::GameOver,
+                )
+                .with_system(RunStats::reset.system()),
+            );
+    }
+}
+
+// lets the summary screen wait until the
+// run has been added to the leaderboard
+#[derive(
+    SystemLabel, Debug, Clone, PartialEq, Eq, Hash,
+)]
+pub enum StatsSystem {
+    Submit,
+}
+
+// everything that's happened so far this run
+#[derive(Debug, Default)]
+pub struct RunStats {
+    pub kills: u32,
+    pub shots: u32,
+    // how many of those shots hit an enemy
+    pub hits: u32,
+    // how long each cleared level took in seconds
+    // (including any attempts that lost a life)
+    pub level_times: Vec<f32>,
+    // how long has been spent on the current level
+    pub current_time: f32,
+    // the items taken in the order they were taken
+    pub items: Vec<item::ItemId>,
+}
+
+impl RunStats {
+    // how many levels have been beaten
+    pub fn levels_cleared(&self) -> u32 {
+        self.level_times.len() as u32
+    }
+
+    // how much of the time a shot hits as a percentage
+    pub fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots as f32 * 100.0
+        }
+    }
+
+    // how long the whole run has taken
+    pub fn total_time(&self) -> f32 {
+        self.level_times.iter().sum::<f32>()
+            + self.current_time
+    }
+
+    // counts up everything the game reports happening
+    fn record(
+        mut stats: ResMut<RunStats>,
+        mut events: EventReader<state::GameEvent>,
+    ) {
+        for event in events.iter() {
+            match event {
+                state::GameEvent::ShotFired => {
+                    stats.shots += 1
+                }
+                state::GameEvent::EnemyHit => {
+                    stats.hits += 1
+                }
+                state::GameEvent::EnemyKilled => {
+                    stats.kills += 1
+                }
+                state::GameEvent::LevelClear => {
+                    let time = stats.current_time;
+                    stats.level_times.push(time);
+                    stats.current_time = 0.0;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // keeps track of the time spent actually playing
+    fn tick(
+        clock: Res<clock::Clock>,
+        mut stats: ResMut<RunStats>,
+    ) {
+        stats.current_time += clock.delta_seconds();
+    }
+
+    // takes a copy of the player's items
+    fn note_items(
+        mut stats: ResMut<RunStats>,
+        items: Res<item::ItemManager>,
+    ) {
+        stats.items = items.ids().to_vec();
+    }
+
+    // starts counting again for the next run
+    fn reset(mut stats: ResMut<RunStats>) {
+        *stats = RunStats::default();
+    }
+}
+
+// a finished run as it's remembered on the leaderboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub seed: u64,
+    pub levels_cleared: u32,
+    pub kills: u32,
+    pub accuracy: f32,
+    pub time: f32,
+}
+
+// the best runs that have been played
+// stored best first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub entries: Vec<RunRecord>,
+    // where the run that just ended placed
+    // (None if it didn't make it on)
+    #[serde(skip)]
+    pub latest: Option<usize>,
+}
+
+impl FromWorld for Leaderboard {
+    // loading the saved leaderboard, or starting a new
+    // one if there isn't one or it can't be read
+    fn from_world(_: &mut World) -> Self {
+        storage::read(Leaderboard::KEY)
+            .and_then(|text| ron::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Leaderboard {
+    // how many runs are kept
+    pub const SIZE: usize = 10;
+    // the name the leaderboard is stored under
+    const KEY: &'static str = "leaderboard";
+
+    // adds the run that's just ended to the leaderboard
+    // and saves it if it was good enough to stay on it
+    fn submit(
+        mut leaderboard: ResMut<Leaderboard>,
+        stats: Res<RunStats>,
+        run_seed: Res<seed::RunSeed>,
+    ) {
+        let record = RunRecord {
+            seed: run_seed.value(),
+            levels_cleared: stats.levels_cleared(),
+            kills: stats.kills,
+            accuracy: stats.accuracy(),
+            time: stats.total_time(),
+        };
+        // more levels is better, then more kills
+        // and then being quicker about it
+        let rank = leaderboard
+            .entries
+            .iter()
+            .position(|other| {
+                (record.levels_cleared, record.kills)
+                    > (other.levels_cleared, other.kills)
+                    || (record.levels_cleared, record.kills)
+                        == (
+                            other.levels_cleared,
+                            other.kills,
+                        )
+                        && record.time < other.time
+            })
+            .unwrap_or(leaderboard.entries.len());
+        if rank >= Self::SIZE {
+            leaderboard.latest = None;
+            return;
+        }
+        leaderboard.entries.insert(rank, record);
+        leaderboard.entries.truncate(Self::SIZE);
+        leaderboard.latest = Some(rank);
+        let result = ron::to_string(&*leaderboard)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                storage::write(Self::KEY, &text)
+            });
+        if let Err(e) = result {
+            error!("Couldn't save the leaderboard: {}", e);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// a tiny key value store for anything the game needs
+// to remember between sessions, on native each key is
+// a file in the saves folder and on the web it's
+// the browser's local storage since there's no filesystem
+
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::{fs, path::PathBuf};
+
+    // where all the files are kept
+    const DIR: &str = "saves";
+
+    fn path(key: &str) -> PathBuf {
+        PathBuf::from(DIR).join(format!("{}.ron", key))
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        fs::read_to_string(path(key)).ok()
+    }
+
+    pub fn write(
+        key: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        fs::create_dir_all(DIR)
+            .and_then(|_| fs::write(path(key), value))
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn remove(key: &str) {
+        // not existing in the first place is fine
+        let _ = fs::remove_file(path(key));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    // prefixing the keys so they don't clash with
+    // anything else on the same page
+    fn key(key: &str) -> String {
+        format!("neon-daze.{}", key)
+    }
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn read(name: &str) -> Option<String> {
+        storage()?.get_item(&key(name)).ok()?
+    }
+
+    pub fn write(
+        name: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        storage()
+            .ok_or_else(|| "no local storage".to_string())?
+            .set_item(&key(name), value)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn remove(name: &str) {
+        if let Some(storage) = storage() {
+            let _ = storage.remove_item(&key(name));
+        }
+    }
+}
+
+pub use backend::{read, remove, write};